@@ -0,0 +1,43 @@
+//! Times sorts that go through the scalar `gt_mask`. Run it with and without the
+//! `aggressive-inline` feature to compare the two inlining policies.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use djbsort::{seeded_random_vec, ConstantTimeSort};
+
+const ROUNDS: usize = 2_000;
+
+fn bench<T: Copy>(name: &str, inputs: &[Vec<T>], sort: impl Fn(&mut [T])) {
+    let mut scratch = inputs[0].to_vec();
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        scratch.copy_from_slice(&inputs[round % inputs.len()]);
+        sort(black_box(&mut scratch));
+    }
+    let per_sort = start.elapsed() / ROUNDS as u32;
+    println!("{:<24} {:>10.2?}", name, per_sort);
+}
+
+fn main() {
+    let policy = if cfg!(feature = "aggressive-inline") {
+        "inline(always)"
+    } else {
+        "inline(never)"
+    };
+    println!("gt_mask: {}", policy);
+
+    for len in [16, 256, 4096] {
+        let inputs: Vec<Vec<u64>> = (0..16).map(|seed| seeded_random_vec(len, seed)).collect();
+        let as_i32: Vec<Vec<i32>> = inputs
+            .iter()
+            .map(|v| v.iter().map(|&x| x as i32).collect())
+            .collect();
+        let as_u128: Vec<Vec<u128>> = inputs
+            .iter()
+            .map(|v| v.iter().map(|&x| x as u128).collect())
+            .collect();
+        bench(&format!("[i32; {}]", len), &as_i32, |s| s.ct_sort());
+        bench(&format!("[u128; {}]", len), &as_u128, |s| s.ct_sort());
+    }
+}
@@ -0,0 +1,28 @@
+//! Times `ct_sort` on `[u32]` at powers of two and at the lengths just above them, which the
+//! network handles on its general path.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use djbsort::{seeded_random_vec, ConstantTimeSort};
+
+const ROUNDS: usize = 2_000;
+
+fn bench(len: usize) {
+    let inputs: Vec<Vec<u32>> = (0..16).map(|seed| seeded_random_vec(len, seed)).collect();
+    let mut scratch = inputs[0].to_vec();
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        scratch.copy_from_slice(&inputs[round % inputs.len()]);
+        black_box(&mut scratch[..]).ct_sort();
+    }
+    let per_sort = start.elapsed() / ROUNDS as u32;
+    println!("{:<24} {:>10.2?}", format!("[u32; {}]", len), per_sort);
+}
+
+fn main() {
+    for len in [256, 1024, 4096] {
+        bench(len);
+        bench(len + 1);
+    }
+}
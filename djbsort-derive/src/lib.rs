@@ -0,0 +1,185 @@
+//! `#[derive(FixedWidthKey)]` for djbsort. Enable it through djbsort's `derive` feature rather
+//! than depending on this crate directly.
+//!
+//! The input is parsed with `proc_macro` alone, so the derive has no dependencies. It accepts
+//! structs without generic parameters, with named or tuple fields, exactly one of which is marked
+//! `#[sort_key]`.
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Implements `FixedWidthKey`, `CtOrd` and `ConditionalSwap` for a struct with one field marked
+/// `#[sort_key]`. The key is that field, and a conditional swap exchanges every field.
+#[proc_macro_derive(FixedWidthKey, attributes(sort_key))]
+pub fn derive_fixed_width_key(input: TokenStream) -> TokenStream {
+    let code = match expand(input) {
+        Ok(code) => code,
+        Err(message) => format!("compile_error!({:?});", message),
+    };
+    code.parse().expect("generated code is valid")
+}
+
+struct Field {
+    /// The field name, or its index for a tuple struct.
+    name: String,
+    ty: String,
+    is_key: bool,
+}
+
+fn expand(input: TokenStream) -> Result<String, String> {
+    let mut tokens = input.into_iter().peekable();
+    let mut name = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "struct" => {
+                name = match tokens.next() {
+                    Some(TokenTree::Ident(ident)) => Some(ident.to_string()),
+                    _ => return Err("expected a struct name".into()),
+                };
+                break;
+            }
+            TokenTree::Ident(ident)
+                if ident.to_string() == "enum" || ident.to_string() == "union" =>
+            {
+                return Err("FixedWidthKey can only be derived for structs".into());
+            }
+            _ => {}
+        }
+    }
+    let name = name.ok_or("expected a struct")?;
+    let fields = match tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("FixedWidthKey cannot be derived for generic structs".into());
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            parse_fields(group.stream(), true)?
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            parse_fields(group.stream(), false)?
+        }
+        _ => return Err("FixedWidthKey needs a struct with fields".into()),
+    };
+
+    let mut keys = fields.iter().filter(|field| field.is_key);
+    let key = match (keys.next(), keys.next()) {
+        (Some(key), None) => key,
+        _ => return Err("exactly one field must be marked #[sort_key]".into()),
+    };
+    let swaps: String = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "::djbsort::ConditionalSwap::conditional_swap(&mut a.{0}, &mut b.{0}, mask);",
+                field.name
+            )
+        })
+        .collect();
+    Ok(format!(
+        "impl ::djbsort::FixedWidthKey for {name} {{
+            type Key = {key_ty};
+
+            #[inline(always)]
+            fn key(&self) -> {key_ty} {{
+                self.{key}
+            }}
+        }}
+
+        impl ::djbsort::ConditionalSwap for {name} {{
+            #[inline(always)]
+            fn conditional_swap(a: &mut Self, b: &mut Self, mask: ::djbsort::Mask) {{
+                {swaps}
+            }}
+        }}
+
+        impl ::djbsort::CtOrd for {name} {{
+            #[inline(always)]
+            fn ct_gt_mask(self, other: Self) -> ::djbsort::Mask {{
+                ::djbsort::CtOrd::ct_gt_mask(self.{key}, other.{key})
+            }}
+        }}",
+        name = name,
+        key = key.name,
+        key_ty = key.ty,
+        swaps = swaps,
+    ))
+}
+
+/// Splits the body of a struct into its fields. Commas inside angle brackets belong to a type,
+/// so the nesting depth is tracked, skipping the `>` of `->`.
+fn parse_fields(body: TokenStream, named: bool) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    let mut current = Vec::new();
+    let mut depth = 0usize;
+    let mut after_minus = false;
+    for token in body {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                ',' if depth == 0 => {
+                    fields.push(parse_field(
+                        core::mem::take(&mut current),
+                        named,
+                        fields.len(),
+                    )?);
+                    after_minus = false;
+                    continue;
+                }
+                '<' => depth += 1,
+                '>' if !after_minus => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            after_minus = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            after_minus = false;
+        }
+        current.push(token);
+    }
+    if !current.is_empty() {
+        fields.push(parse_field(current, named, fields.len())?);
+    }
+    Ok(fields)
+}
+
+fn parse_field(tokens: Vec<TokenTree>, named: bool, index: usize) -> Result<Field, String> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut is_key = false;
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next();
+        if let Some(TokenTree::Group(attribute)) = tokens.next() {
+            let path = attribute.stream().into_iter().next().map(|t| t.to_string());
+            is_key |= path.as_deref() == Some("sort_key");
+        }
+    }
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next();
+                }
+            }
+        }
+    }
+    let name = if named {
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident.to_string(),
+            _ => return Err("expected a field name".into()),
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+            _ => return Err("expected `:` after the field name".into()),
+        }
+        name
+    } else {
+        index.to_string()
+    };
+    let ty: TokenStream = tokens.collect();
+    Ok(Field {
+        name,
+        ty: ty.to_string(),
+        is_key,
+    })
+}
@@ -0,0 +1,33 @@
+#![no_main]
+
+use std::convert::TryInto;
+use std::mem::size_of;
+
+use djbsort::ConstantTimeSort;
+use libfuzzer_sys::fuzz_target;
+
+/// Reads `data` as little-endian `$t`s, dropping a trailing partial element, and checks
+/// `ct_sort` against `slice::sort`.
+macro_rules! check_sort {
+    ($data:expr, $t:ty) => {{
+        let mut vec: Vec<$t> = $data
+            .chunks_exact(size_of::<$t>())
+            .map(|chunk| <$t>::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        let mut expected = vec.clone();
+        vec.ct_sort();
+        expected.sort();
+        assert_eq!(vec, expected);
+    }};
+}
+
+fuzz_target!(|data: &[u8]| {
+    check_sort!(data, u8);
+    check_sort!(data, u16);
+    check_sort!(data, u32);
+    check_sort!(data, u64);
+    check_sort!(data, u128);
+    check_sort!(data, i8);
+    check_sort!(data, i32);
+    check_sort!(data, i64);
+});
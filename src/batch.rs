@@ -0,0 +1,133 @@
+use crate::{compare_exchange_at, network, CtOrd};
+
+/// Sorts every slice in `slices` ascending. The network for the shared length is traversed once,
+/// and each of its comparators is applied to all slices before moving on to the next.
+///
+/// Panics if the slices do not all have the same length.
+pub fn ct_sort_batch<T: CtOrd>(slices: &mut [&mut [T]]) {
+    let len = match slices.first() {
+        Some(slice) => slice.len(),
+        None => return,
+    };
+    assert!(
+        slices.iter().all(|slice| slice.len() == len),
+        "all slices in a batch must have the same length"
+    );
+    network(len, |i, j| {
+        for slice in slices.iter_mut() {
+            compare_exchange_at(slice, i, j);
+        }
+    });
+}
+
+/// Sorting each row of a matrix with a fixed number of columns.
+pub trait ConstantTimeSortRows {
+    /// Sorts every row ascending, on its own. As with [`ct_sort_batch`], the network for the
+    /// row length is traversed once and each comparator is applied to all rows in turn.
+    fn ct_sort_rows(&mut self);
+
+    /// Sorts every column ascending, on its own, so that each column is non-decreasing from
+    /// the first row to the last. The network for the number of rows is traversed once, and
+    /// each comparator exchanges the two rows it names column by column, so the strided accesses
+    /// depend only on the shape of the matrix.
+    fn ct_sort_columns(&mut self);
+}
+
+impl<T: CtOrd, const C: usize> ConstantTimeSortRows for [[T; C]] {
+    fn ct_sort_rows(&mut self) {
+        network(C, |i, j| {
+            for row in self.iter_mut() {
+                compare_exchange_at(row, i, j);
+            }
+        });
+    }
+
+    fn ct_sort_columns(&mut self) {
+        network(self.len(), |i, j| {
+            let (head, tail) = self.split_at_mut(j);
+            for (a, b) in head[i].iter_mut().zip(tail[0].iter_mut()) {
+                let mask = a.ct_gt_mask(*b);
+                T::conditional_swap(a, b, mask);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ct_sort_batch, ConstantTimeSortRows};
+
+    #[test]
+    fn sort_batch_random() {
+        for len in 0..64 {
+            let mut rows: Vec<Vec<i16>> = (0..10)
+                .map(|_| rand::rng().random_iter().take(len).collect())
+                .collect();
+            let mut expected = rows.to_vec();
+            let mut slices: Vec<&mut [i16]> = rows.iter_mut().map(|row| &mut row[..]).collect();
+            ct_sort_batch(&mut slices);
+            expected.iter_mut().for_each(|row| row.sort());
+            assert_eq!(rows, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_batch_length_mismatch() {
+        let (mut a, mut b) = ([3u8, 2, 1], [2u8, 1]);
+        ct_sort_batch(&mut [&mut a[..], &mut b[..]]);
+    }
+
+    macro_rules! sort_rows_random {
+        ($name:ident, $t:ty, $($c:expr),+) => {
+            #[test]
+            fn $name() {
+                $(
+                    for count in 0..8 {
+                        let mut rows: Vec<[$t; $c]> = (0..count)
+                            .map(|_| rand::rng().random())
+                            .collect();
+                        let mut expected = rows.to_vec();
+                        rows.ct_sort_rows();
+                        expected.iter_mut().for_each(|row| row.sort());
+                        assert_eq!(rows, expected);
+                    }
+                )+
+            }
+        };
+    }
+
+    sort_rows_random!(sort_rows_u32_random, u32, 0, 1, 2, 3, 7, 8, 9, 16, 31);
+    sort_rows_random!(sort_rows_i8_random, i8, 1, 5, 32);
+
+    macro_rules! sort_columns_random {
+        ($name:ident, $t:ty, $($c:expr),+) => {
+            #[test]
+            fn $name() {
+                $(
+                    for count in 0..40 {
+                        let mut rows: Vec<[$t; $c]> = (0..count)
+                            .map(|_| rand::rng().random())
+                            .collect();
+                        // Transpose, sort the rows and transpose back.
+                        let mut columns: Vec<Vec<$t>> = (0..$c)
+                            .map(|c| rows.iter().map(|row| row[c]).collect())
+                            .collect();
+                        columns.iter_mut().for_each(|column| column.sort());
+                        let expected: Vec<[$t; $c]> = (0..count)
+                            .map(|r| core::array::from_fn(|c| columns[c][r]))
+                            .collect();
+                        rows.ct_sort_columns();
+                        assert_eq!(rows, expected);
+                    }
+                )+
+            }
+        };
+    }
+
+    sort_columns_random!(sort_columns_u32_random, u32, 0, 1, 2, 3, 8, 17);
+    sort_columns_random!(sort_columns_i8_random, i8, 1, 5, 32);
+}
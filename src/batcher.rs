@@ -0,0 +1,105 @@
+//! Batcher's sorting networks, as alternatives to the default one. They sort just as correctly
+//! but differ in comparator count and access pattern.
+
+use crate::merge::bitonic_merge_network;
+
+/// Calls `compare_exchange(i, j)` for every comparator of Batcher's bitonic sorting network for
+/// `len` elements, in execution order, where the smaller value is to end up at `i`. Unlike the
+/// other networks, `i` exceeds `j` for the comparators of descending merges. The sequence
+/// depends only on `len`.
+pub(crate) fn bitonic_network<F: FnMut(usize, usize)>(len: usize, mut compare_exchange: F) {
+    bitonic_sort_network(0, len, false, &mut compare_exchange);
+}
+
+/// Sorts `lo..lo + len` by sorting the halves in opposite directions, which leaves a bitonic
+/// sequence for the merge.
+fn bitonic_sort_network<F: FnMut(usize, usize)>(
+    lo: usize,
+    len: usize,
+    descending: bool,
+    compare_exchange: &mut F,
+) {
+    if len > 1 {
+        let m = len / 2;
+        bitonic_sort_network(lo, m, !descending, compare_exchange);
+        bitonic_sort_network(lo + m, len - m, descending, compare_exchange);
+        if descending {
+            bitonic_merge_network(lo, len, &mut |i, j| compare_exchange(j, i));
+        } else {
+            bitonic_merge_network(lo, len, compare_exchange);
+        }
+    }
+}
+
+/// Calls `compare_exchange(i, j)`, with `i < j`, for every comparator of Batcher's odd-even
+/// merge sort for `len` elements, in execution order. This is the merge exchange formulation of
+/// Knuth's Algorithm 5.2.2M, which handles any length without padding. The sequence depends
+/// only on `len`.
+pub(crate) fn odd_even_network<F: FnMut(usize, usize)>(len: usize, mut compare_exchange: F) {
+    if len < 2 {
+        return;
+    }
+    let top = 1 << (usize::BITS - 1 - (len - 1).leading_zeros());
+    let mut p = top;
+    while p > 0 {
+        let (mut q, mut r, mut d) = (top, 0, p);
+        loop {
+            for i in (0..len - d).filter(|i| i & p == r) {
+                compare_exchange(i, i + d);
+            }
+            if q == p {
+                break;
+            }
+            d = q - p;
+            q >>= 1;
+            r = p;
+        }
+        p >>= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ConstantTimeSort;
+
+    macro_rules! bitonic_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort_bitonic();
+                    expected.ct_sort();
+                    assert_eq!(vec, expected);
+                }
+            }
+        };
+    }
+
+    bitonic_random!(bitonic_u8_random, u8, 300);
+    bitonic_random!(bitonic_u32_random, u32, 300);
+    bitonic_random!(bitonic_i64_random, i64, 300);
+
+    macro_rules! odd_even_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort_oddeven();
+                    expected.ct_sort();
+                    assert_eq!(vec, expected);
+                }
+            }
+        };
+    }
+
+    odd_even_random!(odd_even_u8_random, u8, 300);
+    odd_even_random!(odd_even_u32_random, u32, 300);
+    odd_even_random!(odd_even_i64_random, i64, 300);
+}
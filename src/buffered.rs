@@ -0,0 +1,89 @@
+use crate::{ConstantTimeSort, CtOrd};
+
+/// Like [`ct_argsort`](ConstantTimeSort::ct_argsort), but writes the sorting permutation to `out`
+/// and uses the first `slice.len()` elements of `scratch` instead of allocating, so that it needs
+/// no allocator and can run in a loop with buffers the caller keeps. The scratch contents on
+/// entry do not matter; on return they hold copies of the sorted elements, which the caller may
+/// want to wipe.
+///
+/// Panics if `out.len() != slice.len()`, if `scratch` is shorter than `slice`, or if the slice has
+/// more than `u32::MAX` elements.
+pub fn ct_argsort_in<T: CtOrd>(slice: &[T], out: &mut [u32], scratch: &mut [(T, u32)]) {
+    assert_eq!(
+        out.len(),
+        slice.len(),
+        "output length must equal the input length"
+    );
+    let pairs = sorted_pairs(slice, scratch);
+    for (dst, &(_, index)) in out.iter_mut().zip(pairs.iter()) {
+        *dst = index;
+    }
+}
+
+/// Like [`ct_sort_stable`](ConstantTimeSort::ct_sort_stable), but uses the first `slice.len()`
+/// elements of `scratch` instead of allocating. As with [`ct_argsort_in`], the scratch ends up
+/// holding copies of the sorted elements.
+///
+/// Panics if `scratch` is shorter than `slice` or if the slice has more than `u32::MAX` elements.
+pub fn ct_sort_stable_in<T: CtOrd>(slice: &mut [T], scratch: &mut [(T, u32)]) {
+    let pairs = sorted_pairs(slice, scratch);
+    for (dst, &(src, _)) in slice.iter_mut().zip(pairs.iter()) {
+        *dst = src;
+    }
+}
+
+/// Pairs every element with its index in the front of `scratch` and sorts the pairs, breaking
+/// ties by index.
+fn sorted_pairs<'a, T: CtOrd>(slice: &[T], scratch: &'a mut [(T, u32)]) -> &'a [(T, u32)] {
+    assert!(
+        slice.len() <= u32::MAX as usize,
+        "slice too long for u32 indices"
+    );
+    assert!(scratch.len() >= slice.len(), "scratch buffer too short");
+    let pairs = &mut scratch[..slice.len()];
+    for (pair, (&x, index)) in pairs.iter_mut().zip(slice.iter().zip(0..)) {
+        *pair = (x, index);
+    }
+    pairs.ct_sort();
+    pairs
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use rand::RngExt;
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::tests::LowByte;
+    use crate::{ct_argsort_in, ct_sort_stable_in, ConstantTimeSort};
+
+    #[test]
+    fn argsort_in_reuses_scratch() {
+        let mut scratch = vec![(LowByte(0), 0); 200];
+        let mut out = vec![0; 200];
+        for count in 0..200 {
+            let vec: Vec<LowByte> = rand::rng().random_iter().take(count).map(LowByte).collect();
+            ct_argsort_in(&vec, &mut out[..count], &mut scratch);
+            assert_eq!(out[..count], vec.ct_argsort()[..]);
+        }
+    }
+
+    #[test]
+    fn sort_stable_in_reuses_scratch() {
+        let mut scratch = vec![(LowByte(0), 0); 200];
+        for count in 0..200 {
+            let mut vec: Vec<LowByte> =
+                rand::rng().random_iter().take(count).map(LowByte).collect();
+            let mut expected = vec.to_vec();
+            ct_sort_stable_in(&mut vec, &mut scratch);
+            expected.sort_by_key(|x| x.0 as u8);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_stable_in_scratch_too_short() {
+        ct_sort_stable_in(&mut [3u32, 1, 2], &mut [(0, 0); 2]);
+    }
+}
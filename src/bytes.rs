@@ -0,0 +1,301 @@
+use core::fmt;
+use core::mem::{align_of, size_of};
+
+use crate::{conditional_swap_at, network, ConditionalSwap, ConstantTimeSort, CtOrd};
+
+/// Integer types for which every byte pattern of the right size is a valid value, and which may
+/// therefore be sorted in place inside a byte buffer.
+pub trait FromBytes: CtOrd + sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! generate_from_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+            impl FromBytes for $t {}
+        )*
+    };
+}
+
+generate_from_bytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Why a byte buffer could not be viewed as a slice of `T`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BytesError {
+    /// The buffer does not start at a multiple of `T`'s alignment.
+    Misaligned,
+    /// The buffer length is not a multiple of `T`'s size.
+    Length,
+}
+
+impl fmt::Display for BytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BytesError::Misaligned => f.write_str("byte buffer is misaligned for the element type"),
+            BytesError::Length => {
+                f.write_str("byte buffer length is not a multiple of the element size")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BytesError {}
+
+/// Sorts `bytes` in place as a slice of native-endian `T`s, without copying. Only the pointer
+/// and the length are checked, so failure leaks nothing about the contents.
+pub fn ct_sort_bytes_as<T: FromBytes>(bytes: &mut [u8]) -> Result<(), BytesError> {
+    if !(bytes.as_ptr() as usize).is_multiple_of(align_of::<T>()) {
+        return Err(BytesError::Misaligned);
+    }
+    if !bytes.len().is_multiple_of(size_of::<T>()) {
+        return Err(BytesError::Length);
+    }
+    // SAFETY: the pointer is aligned for `T`, the length covers whole elements, the borrow is
+    // exclusive, and `FromBytes` is only implemented for integers, which have no invalid
+    // byte patterns.
+    let slice = unsafe {
+        core::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut T, bytes.len() / size_of::<T>())
+    };
+    slice.ct_sort();
+    Ok(())
+}
+
+/// Marks a fieldless `#[repr(u8)]` enum, so that slices of it can be sorted by discriminant with
+/// [`ct_sort_repr_u8`].
+///
+/// # Safety
+///
+/// The type must be `#[repr(u8)]` with no fields in any variant, so that each value is exactly
+/// one initialized byte holding its discriminant.
+pub unsafe trait ReprU8: Copy {}
+
+/// Rejects a type that is not one byte wide when `ct_sort_repr_u8` is instantiated.
+struct OneByte<E>(core::marker::PhantomData<E>);
+
+impl<E> OneByte<E> {
+    const CHECK: () = assert!(
+        size_of::<E>() == 1 && align_of::<E>() == 1,
+        "type is not one byte wide"
+    );
+}
+
+/// Sorts a slice of a fieldless `#[repr(u8)]` enum ascending by discriminant, by sorting its
+/// bytes as `u8`s. A type that is not one byte wide fails to compile.
+///
+/// ```
+/// use djbsort::{ct_sort_repr_u8, ReprU8};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Suit {
+///     Clubs = 1,
+///     Diamonds = 2,
+///     Hearts = 4,
+///     Spades = 8,
+/// }
+///
+/// // SAFETY: `Suit` is a fieldless `#[repr(u8)]` enum.
+/// unsafe impl ReprU8 for Suit {}
+///
+/// let mut hand = [Suit::Spades, Suit::Clubs, Suit::Hearts, Suit::Clubs, Suit::Diamonds];
+/// ct_sort_repr_u8(&mut hand);
+/// assert_eq!(hand, [Suit::Clubs, Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]);
+/// ```
+pub fn ct_sort_repr_u8<E: ReprU8>(slice: &mut [E]) {
+    let () = OneByte::<E>::CHECK;
+    // SAFETY: `ReprU8` guarantees that every `E` is one initialized byte, so the slice can be
+    // viewed as bytes. Sorting only permutes those bytes, so each of them still holds the
+    // discriminant of a variant when the slice is viewed as `E`s again.
+    let bytes =
+        unsafe { core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut u8, slice.len()) };
+    bytes.ct_sort();
+}
+
+/// Sorts `count` 4-bit values packed two per byte ascending in place. Value `k` is the low nibble
+/// of `bytes[k / 2]` if `k` is even and the high nibble if it is odd. Each comparator extracts its
+/// two nibbles, compares and conditionally swaps them as `u8`s and writes them back, so nothing is
+/// unpacked up front. With an odd `count`, the high nibble of the last byte used is left as it is.
+///
+/// ```
+/// use djbsort::ct_sort_nibbles;
+///
+/// let mut bytes = [0x3a, 0x71, 0x02];
+/// ct_sort_nibbles(&mut bytes, 5);
+/// assert_eq!(bytes, [0x21, 0x73, 0x0a]);
+/// ```
+///
+/// Panics if `count` exceeds `2 * bytes.len()`.
+pub fn ct_sort_nibbles(bytes: &mut [u8], count: usize) {
+    assert!(
+        count / 2 + count % 2 <= bytes.len(),
+        "count exceeds the nibbles in the buffer"
+    );
+    let shift = |k: usize| 4 * (k as u32 & 1);
+    let get = |bytes: &[u8], k: usize| bytes[k / 2] >> shift(k) & 0xF;
+    let set = |bytes: &mut [u8], k: usize, nibble: u8| {
+        let byte = &mut bytes[k / 2];
+        *byte = *byte & !(0xF << shift(k)) | nibble << shift(k);
+    };
+    network(count, |i, j| {
+        let (mut a, mut b) = (get(bytes, i), get(bytes, j));
+        let mask = a.ct_gt_mask(b);
+        u8::conditional_swap(&mut a, &mut b, mask);
+        set(bytes, i, a);
+        set(bytes, j, b);
+    });
+}
+
+/// The byte order of an integer field inside a record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Rejects a field that does not fit its record when `ct_sort_by_field` is instantiated.
+struct FieldBounds<const OFF: usize, const W: usize, const RECORD: usize>;
+
+impl<const OFF: usize, const W: usize, const RECORD: usize> FieldBounds<OFF, W, RECORD> {
+    const CHECK: () = assert!(
+        OFF + W <= RECORD,
+        "field extends past the end of the record"
+    );
+}
+
+/// Sorts fixed-size records ascending by the unsigned `W`-byte integer at byte offset `OFF` in
+/// each, stored in the byte order `endian`, and moves whole records. The key is read afresh from
+/// both records at every comparator, so nothing is deserialized up front. A field that does not
+/// fit in `RECORD` bytes fails to compile.
+///
+/// ```
+/// use djbsort::{ct_sort_by_field, Endian};
+///
+/// let mut records = [*b"c\x01\x00", *b"a\x00\x01", *b"b\x02\x00"];
+/// ct_sort_by_field::<1, 2, 3>(&mut records, Endian::Little);
+/// assert_eq!(records, [*b"c\x01\x00", *b"b\x02\x00", *b"a\x00\x01"]);
+/// ```
+pub fn ct_sort_by_field<const OFF: usize, const W: usize, const RECORD: usize>(
+    records: &mut [[u8; RECORD]],
+    endian: Endian,
+) {
+    let () = FieldBounds::<OFF, W, RECORD>::CHECK;
+    let key = |record: &[u8; RECORD]| {
+        let mut key = [0u8; W];
+        key.copy_from_slice(&record[OFF..OFF + W]);
+        if endian == Endian::Little {
+            key.reverse();
+        }
+        key
+    };
+    network(records.len(), |i, j| {
+        let swap = key(&records[i]).ct_gt_mask(key(&records[j]));
+        conditional_swap_at(records, i, j, swap);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{
+        ct_sort_by_field, ct_sort_bytes_as, ct_sort_nibbles, ct_sort_repr_u8, BytesError, Endian,
+        ReprU8,
+    };
+
+    #[test]
+    fn sort_bytes_as_u32() {
+        for count in 0..64 {
+            let mut words: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = words.to_vec();
+            expected.sort();
+            let bytes = unsafe {
+                core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, count * 4)
+            };
+            assert_eq!(ct_sort_bytes_as::<u32>(bytes), Ok(()));
+            assert_eq!(words, expected);
+        }
+    }
+
+    #[test]
+    fn sort_nibbles_random() {
+        for count in 0..100usize {
+            let mut bytes: Vec<u8> = rand::rng().random_iter().take(count.div_ceil(2)).collect();
+            let original = bytes.to_vec();
+            let nibble = |bytes: &[u8], k: usize| bytes[k / 2] >> (4 * (k % 2)) & 0xF;
+            let mut expected: Vec<u8> = (0..count).map(|k| nibble(&original, k)).collect();
+            expected.sort();
+            ct_sort_nibbles(&mut bytes, count);
+            assert!((0..count).map(|k| nibble(&bytes, k)).eq(expected));
+            if count % 2 == 1 {
+                assert_eq!(bytes[count / 2] >> 4, original[count / 2] >> 4);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_nibbles_count_too_large() {
+        ct_sort_nibbles(&mut [0x12, 0x34], 5);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(u8)]
+    enum Level {
+        Low = 3,
+        Medium = 70,
+        High = 200,
+    }
+
+    unsafe impl ReprU8 for Level {}
+
+    #[test]
+    fn sort_repr_u8_random() {
+        for count in 0..64 {
+            let mut levels: Vec<Level> = (0..count)
+                .map(|_| [Level::High, Level::Low, Level::Medium][rand::rng().random_range(0..3)])
+                .collect();
+            let mut expected = levels.to_vec();
+            expected.sort();
+            ct_sort_repr_u8(&mut levels);
+            assert_eq!(levels, expected);
+        }
+    }
+
+    #[test]
+    fn sort_bytes_as_rejects_bad_buffers() {
+        let mut words = [0u64; 4];
+        let bytes = unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, 32) };
+        assert_eq!(
+            ct_sort_bytes_as::<u64>(&mut bytes[..31]),
+            Err(BytesError::Length)
+        );
+        assert_eq!(
+            ct_sort_bytes_as::<u64>(&mut bytes[1..9]),
+            Err(BytesError::Misaligned)
+        );
+        assert_eq!(ct_sort_bytes_as::<u8>(&mut bytes[1..4]), Ok(()));
+    }
+
+    #[test]
+    fn sort_by_field_random() {
+        for count in 0..128 {
+            let mut records: Vec<[u8; 11]> = rand::rng().random_iter().take(count).collect();
+            let mut expected = records.to_vec();
+            ct_sort_by_field::<3, 4, 11>(&mut records, Endian::Little);
+            expected.sort_by_key(|r| u32::from_le_bytes([r[3], r[4], r[5], r[6]]));
+            let key = |r: &[u8; 11]| u32::from_le_bytes([r[3], r[4], r[5], r[6]]);
+            assert!(records.iter().map(key).eq(expected.iter().map(key)));
+            ct_sort_by_field::<9, 2, 11>(&mut records, Endian::Big);
+            assert!(records.windows(2).all(|pair| pair[0][9..] <= pair[1][9..]));
+            let mut sorted = records.to_vec();
+            sorted.sort();
+            expected.sort();
+            assert_eq!(sorted, expected);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+
+use crate::scratch::Scratch;
+use crate::{ct_merge, ConstantTimeSort, CtOrd};
+
+/// Sorts a stream of elements chunk by chunk, then merges the sorted chunks.
+///
+/// Each pushed chunk is sorted with [`ct_sort`](ConstantTimeSort::ct_sort) and copied into the
+/// sorter, which therefore holds every element pushed so far. [`finish`](Self::finish) merges the
+/// runs pairwise with [`ct_merge`], round after round, and needs room for one more copy of the
+/// elements while it does. The number of chunks and their lengths are treated as public: they
+/// decide the merge order, but nothing else does.
+pub struct ChunkedSorter<T: CtOrd> {
+    runs: Vec<Scratch<T>>,
+}
+
+impl<T: CtOrd> ChunkedSorter<T> {
+    pub fn new() -> ChunkedSorter<T> {
+        ChunkedSorter { runs: Vec::new() }
+    }
+
+    /// Sorts `chunk` ascending in place and keeps a copy of it as one sorted run.
+    pub fn push_chunk(&mut self, chunk: &mut [T]) {
+        chunk.ct_sort();
+        self.runs.push(chunk.iter().copied().collect());
+    }
+
+    /// Merges all runs and returns every pushed element, sorted ascending.
+    pub fn finish(self) -> Vec<T> {
+        self.finish_with(ct_merge)
+    }
+
+    /// Like [`finish`](Self::finish), but merges with [`ct_par_merge`](crate::ct_par_merge).
+    /// The result is the same.
+    #[cfg(feature = "parallel")]
+    pub fn finish_par(self) -> Vec<T>
+    where
+        T: Send,
+    {
+        self.finish_with(crate::ct_par_merge)
+    }
+
+    fn finish_with(mut self, merge: fn(&[T], &[T], &mut [T])) -> Vec<T> {
+        while self.runs.len() > 1 {
+            let mut runs = Vec::with_capacity(self.runs.len().div_ceil(2));
+            let mut previous = self.runs.drain(..);
+            while let Some(a) = previous.next() {
+                match previous.next() {
+                    Some(b) => {
+                        let mut out: Scratch<T> = a.iter().chain(b.iter()).copied().collect();
+                        merge(&a, &b, &mut out);
+                        runs.push(out);
+                    }
+                    None => runs.push(a),
+                }
+            }
+            drop(previous);
+            self.runs = runs;
+        }
+        self.runs.pop().map_or_else(Vec::new, |run| run.to_vec())
+    }
+}
+
+impl<T: CtOrd> Default for ChunkedSorter<T> {
+    fn default() -> ChunkedSorter<T> {
+        ChunkedSorter::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ChunkedSorter;
+
+    #[test]
+    fn chunked_sort_random() {
+        for chunks in 0..12 {
+            let mut sorter = ChunkedSorter::new();
+            let mut expected = Vec::new();
+            for _ in 0..chunks {
+                let len = rand::rng().random_range(0..50);
+                let mut chunk: Vec<i32> = rand::rng().random_iter().take(len).collect();
+                expected.extend_from_slice(&chunk);
+                sorter.push_chunk(&mut chunk);
+                assert!(chunk.windows(2).all(|pair| pair[0] <= pair[1]));
+            }
+            expected.sort();
+            assert_eq!(sorter.finish(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn chunked_sort_par_large() {
+        let mut sorter = ChunkedSorter::new();
+        let mut expected = Vec::new();
+        for _ in 0..5 {
+            let mut chunk: Vec<u32> = rand::rng().random_iter().take(10_000).collect();
+            expected.extend_from_slice(&chunk);
+            sorter.push_chunk(&mut chunk);
+        }
+        expected.sort();
+        assert_eq!(sorter.finish_par(), expected);
+    }
+}
@@ -0,0 +1,362 @@
+use core::ops::{BitAnd, BitXor};
+
+use crate::{
+    conditional_swap_at, ct_cswap, network, ConditionalSwap, ConstantTimeSort, CtOrd, Mask,
+};
+
+/// The outcome of a constant-time three-way comparison. It is held as masks rather than as an
+/// enum, so that outcomes can be combined without branching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstantTimeOrdering {
+    less: Mask,
+    greater: Mask,
+}
+
+impl ConstantTimeOrdering {
+    pub const LESS: ConstantTimeOrdering = ConstantTimeOrdering {
+        less: Mask::TRUE,
+        greater: Mask::FALSE,
+    };
+    pub const EQUAL: ConstantTimeOrdering = ConstantTimeOrdering {
+        less: Mask::FALSE,
+        greater: Mask::FALSE,
+    };
+    pub const GREATER: ConstantTimeOrdering = ConstantTimeOrdering {
+        less: Mask::FALSE,
+        greater: Mask::TRUE,
+    };
+
+    /// Builds an ordering from the masks for `a < b` and `a > b`, which must not both be set.
+    #[inline(always)]
+    pub fn from_masks(less: Mask, greater: Mask) -> ConstantTimeOrdering {
+        ConstantTimeOrdering { less, greater }
+    }
+
+    #[inline(always)]
+    pub fn is_lt(self) -> Mask {
+        self.less
+    }
+
+    #[inline(always)]
+    pub fn is_gt(self) -> Mask {
+        self.greater
+    }
+
+    #[inline(always)]
+    pub fn is_eq(self) -> Mask {
+        !(self.less | self.greater)
+    }
+
+    #[inline(always)]
+    pub fn reverse(self) -> ConstantTimeOrdering {
+        ConstantTimeOrdering {
+            less: self.greater,
+            greater: self.less,
+        }
+    }
+
+    /// Returns `self` unless it is equal, in which case `other` decides, as
+    /// [`Ordering::then`](core::cmp::Ordering::then) does.
+    #[inline(always)]
+    pub fn then(self, other: ConstantTimeOrdering) -> ConstantTimeOrdering {
+        let eq = self.is_eq();
+        ConstantTimeOrdering {
+            less: self.less | (eq & other.less),
+            greater: self.greater | (eq & other.greater),
+        }
+    }
+}
+
+/// Returns the constant-time three-way comparison of `a` and `b`.
+#[inline(always)]
+pub fn ct_cmp<T: CtOrd>(a: T, b: T) -> ConstantTimeOrdering {
+    ConstantTimeOrdering::from_masks(b.ct_gt_mask(a), a.ct_gt_mask(b))
+}
+
+/// Returns whether `a` and `b` are equal element by element. Every pair is compared, so the time
+/// does not reveal where the first difference lies. Slices of different lengths are rejected
+/// without looking at them.
+pub fn ct_eq<T: CtOrd>(a: &[T], b: &[T]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .fold(Mask::TRUE, |equal, (&x, &y)| equal & x.ct_eq_mask(y))
+            .into()
+}
+
+/// Sorts by `(primary, secondary)` lexicographically, applying the same permutation to all three
+/// slices with conditional swaps. Each comparator orders by `primary`, and by `secondary` where
+/// the primary keys are equal.
+///
+/// Panics if the three slices differ in length.
+pub fn ct_sort_two_keys<A: CtOrd, B: CtOrd, V: ConditionalSwap>(
+    primary: &mut [A],
+    secondary: &mut [B],
+    payload: &mut [V],
+) {
+    assert!(
+        primary.len() == secondary.len() && primary.len() == payload.len(),
+        "keys and payload differ in length"
+    );
+    network(primary.len(), |i, j| {
+        let (a, b) = (primary[i], primary[j]);
+        let swap = a.ct_gt_mask(b) | (a.ct_eq_mask(b) & secondary[i].ct_gt_mask(secondary[j]));
+        conditional_swap_at(primary, i, j, swap);
+        conditional_swap_at(secondary, i, j, swap);
+        conditional_swap_at(payload, i, j, swap);
+    });
+}
+
+/// Sorts the rows of a table stored as equal-length columns lexicographically: by `columns[0]`,
+/// then by `columns[1]` where those are equal, and so on, applying the same permutation to every
+/// column with conditional swaps. Each comparator folds the greater-than and equality masks of
+/// all columns in order, so it reads every column whatever the data.
+///
+/// Panics if the columns differ in length.
+pub fn ct_sort_multi<T: CtOrd>(columns: &mut [&mut [T]]) {
+    let len = columns.first().map_or(0, |column| column.len());
+    assert!(
+        columns.iter().all(|column| column.len() == len),
+        "columns differ in length"
+    );
+    network(len, |i, j| {
+        let (mut swap, mut equal) = (Mask::FALSE, Mask::TRUE);
+        for column in columns.iter() {
+            let (a, b) = (column[i], column[j]);
+            swap = swap | (equal & a.ct_gt_mask(b));
+            equal = equal & a.ct_eq_mask(b);
+        }
+        for column in columns.iter_mut() {
+            conditional_swap_at(column, i, j, swap);
+        }
+    });
+}
+
+/// Sorting with a caller-supplied comparator.
+pub trait ConstantTimeSortBy<T> {
+    /// Sorts ascending by `cmp` with the default network, moving elements with
+    /// [`ConditionalSwap`]. Elements need not be `Copy`: `Vec`s and `String`s, for example, are
+    /// swapped by their pointer, length and capacity. The sort is only as constant-time as
+    /// `cmp` is.
+    fn ct_sort_by_cmp<F: Fn(&T, &T) -> ConstantTimeOrdering>(&mut self, cmp: F);
+}
+
+impl<T: ConditionalSwap> ConstantTimeSortBy<T> for [T] {
+    fn ct_sort_by_cmp<F: Fn(&T, &T) -> ConstantTimeOrdering>(&mut self, cmp: F) {
+        network(self.len(), |i, j| {
+            let swap = cmp(&self[i], &self[j]).is_gt();
+            conditional_swap_at(self, i, j, swap);
+        });
+    }
+}
+
+/// Sorting with a caller-supplied greater-than mask.
+pub trait ConstantTimeSortByMask<T> {
+    /// Sorts with the default network, using `gt(a, b)` in place of
+    /// [`gt_mask`](crate::GreaterThanMask::gt_mask): it must return all ones if `a` is to be
+    /// placed after `b` and `0` otherwise. The sort is only as constant-time as `gt` is.
+    fn ct_sort_by_mask<F: Fn(T, T) -> T>(&mut self, gt: F);
+}
+
+impl<T> ConstantTimeSortByMask<T> for [T]
+where
+    T: Copy + BitAnd<Output = T> + BitXor<Output = T>,
+{
+    fn ct_sort_by_mask<F: Fn(T, T) -> T>(&mut self, gt: F) {
+        network(self.len(), |i, j| {
+            let (mut a, mut b) = (self[i], self[j]);
+            let mask = gt(a, b);
+            ct_cswap(&mut a, &mut b, mask);
+            self[i] = a;
+            self[j] = b;
+        });
+    }
+}
+
+/// Sorting of `u128` values, such as IPv6 addresses, grouped by a prefix of their high bits.
+pub trait ConstantTimeSortByPrefix {
+    /// Sorts by the top `prefix_bits` bits, breaking ties by the full value, so that values
+    /// sharing a prefix end up next to each other. Since the prefix is the most significant part
+    /// of the value, this order is plain ascending order, and the sort is
+    /// [`ct_sort`](crate::ConstantTimeSort::ct_sort) on the `u128` network once `prefix_bits`
+    /// has been checked.
+    ///
+    /// Panics if `prefix_bits` exceeds 128.
+    fn ct_sort_by_prefix(&mut self, prefix_bits: u32);
+}
+
+impl ConstantTimeSortByPrefix for [u128] {
+    fn ct_sort_by_prefix(&mut self, prefix_bits: u32) {
+        assert!(prefix_bits <= u128::BITS, "prefix_bits exceeds 128");
+        self.ct_sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GreaterThanMask;
+    use crate::{
+        ct_cmp, ct_eq, ct_sort_multi, ct_sort_two_keys, ConstantTimeOrdering, ConstantTimeSortBy,
+        ConstantTimeSortByMask, ConstantTimeSortByPrefix,
+    };
+
+    #[test]
+    fn slices_eq() {
+        assert!(ct_eq::<u32>(&[], &[]));
+        assert!(ct_eq(&[1u32, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1u32, 2, 3], &[1, 2, 4]));
+        assert!(!ct_eq(&[0i8, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1u32, 2, 3], &[1, 2]));
+        assert!(!ct_eq(&[f64::NAN], &[-f64::NAN]));
+    }
+
+    #[test]
+    fn ordering_combinators() {
+        use ConstantTimeOrdering as O;
+
+        assert_eq!(ct_cmp(1u8, 2), O::LESS);
+        assert_eq!(ct_cmp(-3i64, -3), O::EQUAL);
+        assert_eq!(ct_cmp(5u32, 2), O::GREATER);
+        assert_eq!(O::LESS.reverse(), O::GREATER);
+        assert_eq!(O::EQUAL.then(O::LESS), O::LESS);
+        assert_eq!(O::GREATER.then(O::LESS), O::GREATER);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_two_keys_random() {
+        use rand::RngExt;
+        use std::vec::Vec;
+
+        for count in 0..128 {
+            let mut rows: Vec<(u32, u32, u64)> = (0..count)
+                .map(|_| {
+                    let mut rng = rand::rng();
+                    (rng.random_range(0..4), rng.random_range(0..4), rng.random())
+                })
+                .collect();
+            let mut primary: Vec<u32> = rows.iter().map(|r| r.0).collect();
+            let mut secondary: Vec<u32> = rows.iter().map(|r| r.1).collect();
+            let mut payload: Vec<u64> = rows.iter().map(|r| r.2).collect();
+            ct_sort_two_keys(&mut primary, &mut secondary, &mut payload);
+            let sorted: Vec<(u32, u32, u64)> = (0..count)
+                .map(|k| (primary[k], secondary[k], payload[k]))
+                .collect();
+            assert!(sorted
+                .windows(2)
+                .all(|pair| (pair[0].0, pair[0].1) <= (pair[1].0, pair[1].1)));
+            let mut sorted_rows = sorted.to_vec();
+            sorted_rows.sort();
+            rows.sort();
+            assert_eq!(sorted_rows, rows);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_two_keys_length_mismatch() {
+        ct_sort_two_keys(&mut [1u32, 2], &mut [3u32, 4], &mut [5u8]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_multi_random() {
+        use rand::RngExt;
+        use std::vec::Vec;
+
+        for count in 0..100 {
+            let mut rows: Vec<[u32; 3]> = (0..count)
+                .map(|_| {
+                    let mut rng = rand::rng();
+                    [rng.random_range(0..3), rng.random_range(0..3), rng.random()]
+                })
+                .collect();
+            let mut columns: Vec<Vec<u32>> = (0..3)
+                .map(|c| rows.iter().map(|row| row[c]).collect())
+                .collect();
+            let mut views: Vec<&mut [u32]> = columns.iter_mut().map(|c| &mut c[..]).collect();
+            ct_sort_multi(&mut views);
+            rows.sort();
+            assert!((0..count).all(|k| (0..3).all(|c| columns[c][k] == rows[k][c])));
+        }
+        ct_sort_multi::<u32>(&mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_multi_length_mismatch() {
+        ct_sort_multi(&mut [&mut [1u32, 2][..], &mut [3u32][..]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_by_prefix_random() {
+        use rand::RngExt;
+        use std::vec::Vec;
+
+        for prefix_bits in [0, 1, 48, 64, 127, 128] {
+            let prefix = |x: u128| x.checked_shr(128 - prefix_bits).unwrap_or(0);
+            // Few distinct prefixes, so that groups actually form.
+            let mut addresses: Vec<u128> = (0..100)
+                .map(|_| {
+                    let mut rng = rand::rng();
+                    (rng.random_range(0..4u128) << 64) | rng.random::<u64>() as u128
+                })
+                .collect();
+            let mut expected = addresses.to_vec();
+            addresses.ct_sort_by_prefix(prefix_bits);
+            expected.sort_by_key(|&x| (prefix(x), x));
+            assert_eq!(addresses, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_by_prefix_too_long() {
+        [1u128, 0].ct_sort_by_prefix(129);
+    }
+
+    #[test]
+    fn sort_by_reversed_cmp() {
+        let mut array = [3u32, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        array.ct_sort_by_cmp(|a, b| ct_cmp(*a, *b).reverse());
+        assert_eq!(array, [9, 6, 5, 5, 5, 4, 3, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn sort_by_custom_mask() {
+        let mut array = [0x13u16, 0x21, 0x02, 0x35, 0x44, 0x10];
+        array.ct_sort_by_mask(|a, b| b.gt_mask(a));
+        assert_eq!(array, [0x44, 0x35, 0x21, 0x13, 0x10, 0x02]);
+        array.ct_sort_by_mask(|a, b| (a & 0xF).gt_mask(b & 0xF));
+        assert!(array.windows(2).all(|pair| pair[0] & 0xF <= pair[1] & 0xF));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_strings_by_cmp() {
+        use rand::RngExt;
+        use std::string::String;
+        use std::vec::Vec;
+
+        for count in 0..128 {
+            let mut strings: Vec<String> = rand::rng()
+                .random_iter::<u16>()
+                .take(count)
+                .map(|x| std::format!("{}", x % 512))
+                .collect();
+            let mut expected = strings.to_vec();
+            // Order by length, then by descending first byte, all through masks.
+            strings.ct_sort_by_cmp(|a, b| {
+                ct_cmp(a.len(), b.len()).then(ct_cmp(a.as_bytes()[0], b.as_bytes()[0]).reverse())
+            });
+            expected.sort_by(|a, b| {
+                a.len()
+                    .cmp(&b.len())
+                    .then(a.as_bytes()[0].cmp(&b.as_bytes()[0]).reverse())
+            });
+            let key = |s: &String| (s.len(), s.as_bytes()[0]);
+            assert!(strings.iter().map(key).eq(expected.iter().map(key)));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! A C interface mirroring the original djbsort library. Build with `crate-type = ["cdylib"]` or
+//! `["staticlib"]` to link it from C or C++.
+
+use crate::ConstantTimeSort;
+
+macro_rules! generate_ffi_sort {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Sorts `len` `", stringify!($t), "`s at `ptr` in place and ascending.")]
+        /// Does nothing if `ptr` is null.
+        ///
+        /// # Safety
+        ///
+        /// Unless null, `ptr` must be valid for reads and writes of `len` properly aligned,
+        /// initialized elements that nothing else accesses for the duration of the call.
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(ptr: *mut $t, len: usize) {
+            if !ptr.is_null() {
+                core::slice::from_raw_parts_mut(ptr, len).ct_sort();
+            }
+        }
+    };
+}
+
+generate_ffi_sort!(djbsort_u8, u8);
+generate_ffi_sort!(djbsort_u16, u16);
+generate_ffi_sort!(djbsort_u32, u32);
+generate_ffi_sort!(djbsort_u64, u64);
+generate_ffi_sort!(djbsort_i8, i8);
+generate_ffi_sort!(djbsort_i16, i16);
+generate_ffi_sort!(djbsort_i32, i32);
+generate_ffi_sort!(djbsort_i64, i64);
+generate_ffi_sort!(djbsort_f32, f32);
+generate_ffi_sort!(djbsort_f64, f64);
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use super::{djbsort_i64, djbsort_u32};
+
+    #[test]
+    fn ffi_sorts_in_place() {
+        let mut vec: Vec<u32> = rand::rng().random_iter().take(100).collect();
+        let mut expected = vec.to_vec();
+        unsafe { djbsort_u32(vec.as_mut_ptr(), vec.len()) };
+        expected.sort();
+        assert_eq!(vec, expected);
+
+        let mut vec: Vec<i64> = rand::rng().random_iter().take(100).collect();
+        let mut expected = vec.to_vec();
+        unsafe { djbsort_i64(vec.as_mut_ptr(), vec.len()) };
+        expected.sort();
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn ffi_ignores_null() {
+        unsafe { djbsort_u32(core::ptr::null_mut(), 10) };
+    }
+}
@@ -0,0 +1,87 @@
+use crate::ConstantTimeSort;
+
+/// Sorting of Q15 fixed-point samples.
+pub trait ConstantTimeSortQ15 {
+    /// Sorts Q15 samples ascending by value. A Q15 sample is an `i16` read as `x / 2^15`: one
+    /// sign bit and 15 fractional bits, covering `-1.0` (`i16::MIN`) up to `1.0 - 2^-15`
+    /// (`i16::MAX`). The scale is a positive constant, so the signed integer order is the order
+    /// of the values, and this is `ct_sort` on the `i16`s.
+    fn ct_sort_q15(&mut self);
+}
+
+/// Sorting of Q31 fixed-point samples.
+pub trait ConstantTimeSortQ31 {
+    /// Sorts Q31 samples ascending by value. A Q31 sample is an `i32` read as `x / 2^31`,
+    /// covering `-1.0` (`i32::MIN`) up to `1.0 - 2^-31` (`i32::MAX`). As with
+    /// [`ct_sort_q15`](ConstantTimeSortQ15::ct_sort_q15), this is `ct_sort` on the `i32`s.
+    fn ct_sort_q31(&mut self);
+}
+
+impl ConstantTimeSortQ15 for [i16] {
+    fn ct_sort_q15(&mut self) {
+        self.ct_sort();
+    }
+}
+
+impl ConstantTimeSortQ31 for [i32] {
+    fn ct_sort_q31(&mut self) {
+        self.ct_sort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConstantTimeSortQ15, ConstantTimeSortQ31};
+
+    #[test]
+    fn sort_q15_around_zero_and_extremes() {
+        // -1.0, -0.5, -2^-15, 0.0, 2^-15, 0.5, 1.0 - 2^-15, shuffled.
+        let mut samples = [0x4000i16, -1, i16::MAX, 0, i16::MIN, 1, -0x4000];
+        samples.ct_sort_q15();
+        assert_eq!(samples, [i16::MIN, -0x4000, -1, 0, 1, 0x4000, i16::MAX]);
+        let values = samples.map(|x| x as f64 / 32768.0);
+        assert_eq!(
+            values,
+            [
+                -1.0,
+                -0.5,
+                -1.0 / 32768.0,
+                0.0,
+                1.0 / 32768.0,
+                0.5,
+                32767.0 / 32768.0
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_q31_around_zero_and_extremes() {
+        let mut samples = [
+            i32::MAX,
+            1 << 30,
+            -1,
+            i32::MIN + 1,
+            0,
+            i32::MIN,
+            1,
+            -(1 << 30),
+        ];
+        samples.ct_sort_q31();
+        assert_eq!(
+            samples,
+            [
+                i32::MIN,
+                i32::MIN + 1,
+                -(1 << 30),
+                -1,
+                0,
+                1,
+                1 << 30,
+                i32::MAX
+            ]
+        );
+        let values = samples.map(|x| x as f64 / 2147483648.0);
+        assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!((values[0], values[4]), (-1.0, 0.0));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{conditional_swap_at, network, ConstantTimeSort, CtOrd, Mask};
+
+/// Where [`ct_sort_floats`](ConstantTimeFloatSort::ct_sort_floats) puts NaNs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NanPolicy {
+    First,
+    Last,
+}
+
+/// How [`ct_sort_floats`](ConstantTimeFloatSort::ct_sort_floats) orders `-0.0` and `0.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ZeroPolicy {
+    /// `-0.0` sorts before `0.0`, as with `total_cmp` and plain `ct_sort`.
+    Distinct,
+    /// Both zeros compare equal, so they end up next to each other in an unspecified order. The
+    /// values themselves are left as they are.
+    Equal,
+}
+
+/// Sorting floats with the NaNs gathered at one end.
+pub trait ConstantTimeFloatSort {
+    /// Sorts ascending by [`total_cmp`](f64::total_cmp), except that every NaN, whatever its
+    /// sign, goes to the end `nan` selects, and that with [`ZeroPolicy::Equal`] the sign of zero
+    /// is cleared before comparing. The NaNs keep their `total_cmp` order among themselves.
+    /// Plain [`ct_sort`](crate::ConstantTimeSort::ct_sort) corresponds to neither policy: it puts
+    /// NaNs with the sign bit set first and the others last, and keeps the zeros distinct.
+    fn ct_sort_floats(&mut self, nan: NanPolicy, zeros: ZeroPolicy);
+}
+
+macro_rules! generate_float_sort {
+    ($f:ty, $u:ty) => {
+        impl ConstantTimeFloatSort for [$f] {
+            fn ct_sort_floats(&mut self, nan: NanPolicy, zeros: ZeroPolicy) {
+                // NaNs have an all-ones exponent and a nonzero mantissa, so their magnitude bits
+                // exceed those of infinity.
+                let infinity = <$f>::INFINITY.to_bits();
+                let flip = match nan {
+                    NanPolicy::First => 1,
+                    NanPolicy::Last => 0,
+                };
+                let unsign_zero = match zeros {
+                    ZeroPolicy::Distinct => 0,
+                    ZeroPolicy::Equal => 1,
+                };
+                let key = |x: $f| {
+                    let magnitude = x.to_bits() << 1 >> 1;
+                    let is_nan = magnitude.ct_gt_mask(infinity);
+                    let unsign = !magnitude.ct_gt_mask(0) & Mask::from_bit(unsign_zero);
+                    let sign = <$u>::from(unsign.bit());
+                    let x = <$f>::from_bits(x.to_bits() & !(sign << (<$u>::BITS - 1)));
+                    (is_nan.bit() ^ flip, x)
+                };
+                network(self.len(), |i, j| {
+                    let mask = key(self[i]).ct_gt_mask(key(self[j]));
+                    conditional_swap_at(self, i, j, mask);
+                });
+            }
+        }
+    };
+}
+
+generate_float_sort!(f32, u32);
+generate_float_sort!(f64, u64);
+
+/// Sorts IEEE 754 binary16 values, given as their bit patterns, in the order of `total_cmp`, as
+/// `ct_sort` does for `f32` and `f64`: NaNs with the sign bit set first, then `-∞` up to `-0.0`,
+/// `0.0` up to `∞`, and the other NaNs last. Each value is mapped in place to a `u16` key by
+/// flipping the sign bit of non-negative values and every bit of negative ones, the keys go
+/// through the `u16` network, and the mapping is undone. `half::f16` slices can be sorted through
+/// their `to_bits` representation.
+pub fn ct_sort_f16_bits(bits: &mut [u16]) {
+    for x in bits.iter_mut() {
+        *x ^= (*x as i16 >> 15) as u16 | 0x8000;
+    }
+    bits.ct_sort();
+    for x in bits.iter_mut() {
+        *x ^= !(*x as i16 >> 15) as u16 | 0x8000;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ct_sort_f16_bits, ConstantTimeFloatSort, NanPolicy, ZeroPolicy};
+
+    #[test]
+    fn sort_f16_bits_random() {
+        // Orders by sign and magnitude, putting -0.0 just before 0.0, which is what `total_cmp`
+        // does for every bit pattern, NaNs included.
+        let total_order = |x: u16| match x & 0x8000 {
+            0 => x as i32,
+            _ => -((x & 0x7fff) as i32) - 1,
+        };
+        for count in 0..256 {
+            let mut vec: Vec<u16> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            ct_sort_f16_bits(&mut vec);
+            expected.sort_by_key(|&x| total_order(x));
+            assert_eq!(vec, expected);
+        }
+        let (nan, infinity, one) = (0x7e00, 0x7c00, 0x3c00);
+        let mut specials = [nan, 0x8000 | one, 0, infinity, 0x8000, 0x8000 | nan, one];
+        ct_sort_f16_bits(&mut specials);
+        assert_eq!(
+            specials,
+            [0x8000 | nan, 0x8000 | one, 0x8000, 0, one, infinity, nan]
+        );
+    }
+
+    macro_rules! float_sort_nans {
+        ($name:ident, $f:ty, $u:ty) => {
+            #[test]
+            fn $name() {
+                let specials = [
+                    <$f>::NAN,
+                    -<$f>::NAN,
+                    <$f>::from_bits(<$f>::NAN.to_bits() | 1),
+                    <$f>::INFINITY,
+                    <$f>::NEG_INFINITY,
+                    0.0,
+                    -0.0,
+                ];
+                for count in 0..128 {
+                    let vec: Vec<$f> = (0..count)
+                        .map(|_| match rand::rng().random_range(0..3) {
+                            0 => specials[rand::rng().random_range(0..specials.len())],
+                            _ => rand::rng().random::<$f>() - 0.5,
+                        })
+                        .collect();
+                    let (mut nans, mut numbers): (Vec<$f>, Vec<$f>) =
+                        vec.iter().partition(|x| x.is_nan());
+                    nans.sort_by(|a, b| a.total_cmp(b));
+                    numbers.sort_by(|a, b| a.total_cmp(b));
+                    let bits = |v: &[$f]| v.iter().map(|x| x.to_bits()).collect::<Vec<$u>>();
+
+                    let mut first = vec.to_vec();
+                    first.ct_sort_floats(NanPolicy::First, ZeroPolicy::Distinct);
+                    assert_eq!(bits(&first), bits(&[&nans[..], &numbers[..]].concat()));
+
+                    let mut last = vec.to_vec();
+                    last.ct_sort_floats(NanPolicy::Last, ZeroPolicy::Distinct);
+                    assert_eq!(bits(&last), bits(&[&numbers[..], &nans[..]].concat()));
+                }
+            }
+        };
+    }
+
+    float_sort_nans!(float_sort_nans_f32, f32, u32);
+    float_sort_nans!(float_sort_nans_f64, f64, u64);
+
+    macro_rules! float_sort_zeros {
+        ($name:ident, $f:ty) => {
+            #[test]
+            fn $name() {
+                for count in 0..128 {
+                    let vec: Vec<$f> = (0..count)
+                        .map(|_| match rand::rng().random_range(0..4) {
+                            0 => 0.0,
+                            1 => -0.0,
+                            _ => rand::rng().random::<$f>() - 0.5,
+                        })
+                        .collect();
+                    let mut expected = vec.to_vec();
+                    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+                    let mut equal = vec.to_vec();
+                    equal.ct_sort_floats(NanPolicy::Last, ZeroPolicy::Equal);
+                    assert_eq!(equal, expected);
+
+                    let mut distinct = vec.to_vec();
+                    distinct.ct_sort_floats(NanPolicy::Last, ZeroPolicy::Distinct);
+                    expected.sort_by(|a, b| a.total_cmp(b));
+                    assert!(distinct
+                        .iter()
+                        .map(|x| x.to_bits())
+                        .eq(expected.iter().map(|x| x.to_bits())));
+                }
+            }
+        };
+    }
+
+    float_sort_zeros!(float_sort_zeros_f32, f32);
+    float_sort_zeros!(float_sort_zeros_f64, f64);
+}
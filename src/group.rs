@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use crate::{ConstantTimeSort, CtOrd};
+
+/// Sorting followed by a summary of the runs of equal values.
+pub trait ConstantTimeGroup<T> {
+    /// Sorts ascending and returns one `(value, count)` pair per run of equal values, in order.
+    /// The runs are found with
+    /// [`ct_sort_with_unique_mask`](ConstantTimeSort::ct_sort_with_unique_mask), which compares
+    /// every adjacent pair in one pass.
+    ///
+    /// Only the sort is oblivious. The returned `Vec` has one entry per distinct value, so its
+    /// length, and the time to build it, reveal how many there are; use this on data whose value
+    /// distribution is not secret.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    fn ct_sort_grouped(&mut self) -> Vec<(T, u32)>;
+
+    /// Sorts ascending and returns the run-length encoding of the result: the distinct values
+    /// and, at the same positions, their run lengths, which sum to the slice length. The runs are
+    /// those of [`ct_sort_grouped`](Self::ct_sort_grouped), and as there, the length of the
+    /// output reveals the number of distinct values.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    fn ct_sort_rle(&mut self) -> (Vec<T>, Vec<u32>);
+}
+
+impl<T: CtOrd> ConstantTimeGroup<T> for [T] {
+    fn ct_sort_grouped(&mut self) -> Vec<(T, u32)> {
+        assert!(
+            self.len() <= u32::MAX as usize,
+            "slice too long for u32 counts"
+        );
+        let firsts = self.ct_sort_with_unique_mask();
+        let mut groups: Vec<(T, u32)> = Vec::new();
+        for (&x, first) in self.iter().zip(firsts) {
+            if first {
+                groups.push((x, 0));
+            }
+            if let Some(group) = groups.last_mut() {
+                group.1 += 1;
+            }
+        }
+        groups
+    }
+
+    fn ct_sort_rle(&mut self) -> (Vec<T>, Vec<u32>) {
+        self.ct_sort_grouped().into_iter().unzip()
+    }
+}
+
+/// Sorting followed by a running total.
+pub trait ConstantTimeSortPrefixSum {
+    /// Sorts ascending and returns the inclusive prefix sums of the sorted values, widened to
+    /// `u64`, computed in one fixed scan. For 8-, 16- and 32-bit values the sums cannot overflow
+    /// for any slice of up to `2^32` elements. Sums of `u64` and `usize` values wrap modulo
+    /// `2^64` instead of saturating, which keeps the scan free of data-dependent branches; `u128`
+    /// is left out, since its values do not fit the accumulator.
+    fn ct_sort_prefix_sum(&mut self) -> Vec<u64>;
+}
+
+macro_rules! generate_sort_prefix_sum {
+    ($($t:ty),*) => {
+        $(
+            impl ConstantTimeSortPrefixSum for [$t] {
+                fn ct_sort_prefix_sum(&mut self) -> Vec<u64> {
+                    self.ct_sort();
+                    self.iter()
+                        .scan(0u64, |sum, &x| {
+                            *sum = sum.wrapping_add(x as u64);
+                            Some(*sum)
+                        })
+                        .collect()
+                }
+            }
+        )*
+    };
+}
+
+generate_sort_prefix_sum!(u8, u16, u32, u64, usize);
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ConstantTimeGroup, ConstantTimeSortPrefixSum};
+
+    #[test]
+    fn sort_grouped_random() {
+        for count in 0..256 {
+            let mut vec: Vec<i8> = rand::rng()
+                .random_iter::<i8>()
+                .take(count)
+                .map(|x| x % 5)
+                .collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            let groups = vec.ct_sort_grouped();
+            assert_eq!(vec, expected);
+            let mut distinct = expected.to_vec();
+            distinct.dedup();
+            assert!(groups.iter().map(|&(x, _)| x).eq(distinct));
+            for &(x, n) in &groups {
+                assert_eq!(n as usize, expected.iter().filter(|&&y| y == x).count());
+            }
+        }
+    }
+
+    #[test]
+    fn sort_rle_random() {
+        for count in 0..256 {
+            let mut vec: Vec<u16> = rand::rng()
+                .random_iter::<u16>()
+                .take(count)
+                .map(|x| x % 7)
+                .collect();
+            let original = vec.to_vec();
+            let (values, lengths) = vec.ct_sort_rle();
+            assert_eq!(values.len(), lengths.len());
+            assert_eq!(lengths.iter().sum::<u32>() as usize, count);
+            let decoded: Vec<u16> = values
+                .iter()
+                .zip(&lengths)
+                .flat_map(|(&x, &n)| core::iter::repeat_n(x, n as usize))
+                .collect();
+            let mut expected = original;
+            expected.sort();
+            assert_eq!(decoded, expected);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn sort_prefix_sum_random() {
+        for count in 0..256 {
+            let mut vec: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            let sums = vec.ct_sort_prefix_sum();
+            assert_eq!(vec, expected);
+            let mut sum = 0;
+            for (&x, &total) in expected.iter().zip(&sums) {
+                sum += x as u64;
+                assert_eq!(total, sum);
+            }
+            assert_eq!(sums.len(), count);
+        }
+    }
+
+    #[test]
+    fn sort_prefix_sum_wraps_for_u64() {
+        let mut vec = [u64::MAX, 2, u64::MAX];
+        assert_eq!(vec.ct_sort_prefix_sum(), [2, 1, 0]);
+        assert_eq!(vec, [2, u64::MAX, u64::MAX]);
+    }
+}
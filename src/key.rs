@@ -0,0 +1,100 @@
+use crate::scratch::Scratch;
+use crate::{ConditionalSwap, ConstantTimeSort, CtOrd};
+
+/// Sorting by a key derived from each element.
+pub trait ConstantTimeSortByKey<T> {
+    /// Sorts the slice ascending by `f(element)`. The keys are extracted once, up front, into a
+    /// scratch buffer, which is then sorted with [`ct_sort_with`](ConstantTimeSort::ct_sort_with)
+    /// so that the elements follow every swap. `f` is never called inside the network.
+    ///
+    /// Unlike [`slice::sort_by_key`], the sort is not stable.
+    fn ct_sort_by_key<K: CtOrd, F: Fn(&T) -> K>(&mut self, f: F);
+
+    /// Sorts the slice ascending by the `u64` projection `key(element)`, keeping elements with
+    /// equal keys in their original order. `key` runs exactly once per element, before the
+    /// network, and each key is paired with the element's index, so the network sorts
+    /// `(u64, u32)` pairs and the elements follow every swap.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    fn ct_sort_by_u64_key<F: Fn(&T) -> u64>(&mut self, key: F);
+
+    /// Sorts the slice by `hash(element)`, breaking collisions by original index, which with a
+    /// keyed hash amounts to a data-oblivious shuffle of the kind oblivious-RAM constructions
+    /// build on. This is [`ct_sort_by_u64_key`](Self::ct_sort_by_u64_key) with the hash as the
+    /// key, so `hash` runs once per element, before the network. The sort hides the data only if
+    /// `hash` itself runs in constant time.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    fn ct_sort_by_hash<H: Fn(&T) -> u64>(&mut self, hash: H) {
+        self.ct_sort_by_u64_key(hash);
+    }
+}
+
+impl<T: ConditionalSwap> ConstantTimeSortByKey<T> for [T] {
+    fn ct_sort_by_key<K: CtOrd, F: Fn(&T) -> K>(&mut self, f: F) {
+        let mut keys: Scratch<K> = self.iter().map(f).collect();
+        keys.ct_sort_with(self);
+    }
+
+    fn ct_sort_by_u64_key<F: Fn(&T) -> u64>(&mut self, key: F) {
+        assert!(
+            self.len() <= u32::MAX as usize,
+            "slice too long for u32 indices"
+        );
+        let mut keys: Scratch<(u64, u32)> = self.iter().map(key).zip(0..).collect();
+        keys.ct_sort_with(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ConstantTimeSortByKey;
+
+    #[test]
+    fn sort_by_key_random() {
+        for count in 0..256 {
+            let mut vec: Vec<(u32, i16)> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort_by_key(|&(_, key)| key);
+            assert!(vec.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+            let mut sorted = vec.to_vec();
+            sorted.sort();
+            expected.sort();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn sort_by_u64_key_is_stable() {
+        for count in 0..256 {
+            let mut vec: Vec<(u16, u32)> = (0..count)
+                .map(|k| (rand::rng().random_range(0..8), k))
+                .collect();
+            let mut expected = vec.to_vec();
+            let calls = core::cell::Cell::new(0);
+            vec.ct_sort_by_u64_key(|&(timestamp, _)| {
+                calls.set(calls.get() + 1);
+                (timestamp as u64) << 32 | 7
+            });
+            assert_eq!(calls.get(), count);
+            expected.sort_by_key(|&(timestamp, _)| timestamp);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn sort_by_hash_breaks_collisions_by_index() {
+        // A multiplicative hash into 16 buckets, so that collisions are common.
+        let hash = |x: &u32| (*x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 60;
+        for count in 0..256 {
+            let mut vec: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort_by_hash(hash);
+            expected.sort_by_key(hash);
+            assert_eq!(vec, expected);
+        }
+    }
+}
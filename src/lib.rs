@@ -1,129 +1,2145 @@
+#![no_std]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+// Lets the code `#[derive(FixedWidthKey)]` generates name this crate in the tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as djbsort;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{BitAnd, BitOr, BitXor, Not, Range, Shr};
+#[cfg(feature = "alloc")]
+use scratch::Scratch;
+
+mod batch;
+mod batcher;
+mod buffered;
+mod bytes;
+#[cfg(feature = "alloc")]
+mod chunked;
+mod cmp;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fixed;
+mod float;
+#[cfg(feature = "alloc")]
+mod group;
+#[cfg(feature = "alloc")]
+mod key;
+mod merge;
+#[cfg(feature = "alloc")]
+mod packed;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "alloc")]
+mod permutation;
+mod query;
+#[cfg(feature = "alloc")]
+mod scratch;
+#[cfg(feature = "bench")]
+mod seeded;
+mod select;
+#[cfg(feature = "alloc")]
+mod set;
+mod simd;
+mod small;
+#[cfg(feature = "alloc")]
+mod sorted;
+#[cfg(feature = "alloc")]
+mod sorting_network;
+#[cfg(feature = "trace")]
+mod trace;
+#[cfg(feature = "verify")]
+mod verify;
+
+pub use batch::{ct_sort_batch, ConstantTimeSortRows};
+pub use buffered::{ct_argsort_in, ct_sort_stable_in};
+pub use bytes::{
+    ct_sort_by_field, ct_sort_bytes_as, ct_sort_nibbles, ct_sort_repr_u8, BytesError, Endian,
+    FromBytes, ReprU8,
+};
+#[cfg(feature = "alloc")]
+pub use chunked::ChunkedSorter;
+pub use cmp::{
+    ct_cmp, ct_eq, ct_sort_multi, ct_sort_two_keys, ConstantTimeOrdering, ConstantTimeSortBy,
+    ConstantTimeSortByMask, ConstantTimeSortByPrefix,
+};
+#[cfg(feature = "derive")]
+pub use djbsort_derive::FixedWidthKey;
+pub use fixed::{ConstantTimeSortQ15, ConstantTimeSortQ31};
+pub use float::{ct_sort_f16_bits, ConstantTimeFloatSort, NanPolicy, ZeroPolicy};
+#[cfg(feature = "alloc")]
+pub use group::{ConstantTimeGroup, ConstantTimeSortPrefixSum};
+#[cfg(feature = "alloc")]
+pub use key::ConstantTimeSortByKey;
+#[cfg(feature = "alloc")]
+pub use merge::ct_merge_into;
+pub use merge::{ct_merge, ct_merge_in_place, ct_merge_into_in};
+#[cfg(feature = "alloc")]
+pub use packed::ConstantTimeSortStablePacked;
+#[cfg(feature = "parallel")]
+pub use parallel::{
+    ct_par_merge, par_apply_permutation, par_ct_sort_batch, ConstantTimeParallelSort,
+};
+#[cfg(feature = "alloc")]
+pub use permutation::{apply_permutation, invert_permutation};
+pub use query::ConstantTimeQuery;
+#[cfg(feature = "bench")]
+pub use seeded::{seeded_random_vec, SeededRandom};
+pub use select::ConstantTimeSelect;
+#[cfg(feature = "alloc")]
+pub use set::{ct_difference, ct_intersect, ct_multiset_eq, ct_union};
+pub use small::sort_u32x16;
+#[cfg(feature = "alloc")]
+pub use sorted::{ConstantTimeSorted, CtSortIterator};
+#[cfg(feature = "alloc")]
+pub use sorting_network::SortingNetwork;
+#[cfg(feature = "trace")]
+pub use trace::trace_sort;
+#[cfg(feature = "verify")]
+pub use verify::{
+    assert_same_accesses, measure_timing_leak, record_accesses, record_input_accesses, Access,
+    LeakReport,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// The longest slice [`ConstantTimeSort::ct_sort`] handles, which is every length a slice can
+/// have. The network is built from powers of two up to `2^(usize::BITS - 1)` and never forms a
+/// stride or block past `usize::MAX`, so no length overflows it. In practice, a slice of a sized
+/// element type is also capped at `isize::MAX` bytes. The sorts that carry `u32` indices, such as
+/// [`ct_sort_stable`](ConstantTimeSort::ct_sort_stable) and
+/// [`ct_argsort`](ConstantTimeSort::ct_argsort), stop at `u32::MAX` elements instead.
+pub const MAX_LEN: usize = usize::MAX;
+
+/// Why a fallible sort such as
+/// [`try_ct_sort_stable`](ConstantTimeSort::try_ct_sort_stable) refused a slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SortError {
+    /// The slice has more than `max` elements, the most the sort can index.
+    TooLong { max: usize },
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SortError::TooLong { max } => {
+                write!(f, "slice too long: at most {} elements are supported", max)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SortError {}
+
 pub trait ConstantTimeSort {
-    fn ct_sort(&mut self);
+    /// Sorts in the given order. Both directions share the same network; only the mask driving
+    /// each compare-exchange differs. The order itself is not treated as secret.
+    fn ct_sort_by_order(&mut self, order: SortOrder);
+
+    fn ct_sort(&mut self) {
+        self.ct_sort_by_order(SortOrder::Ascending);
+    }
+
+    fn ct_sort_desc(&mut self) {
+        self.ct_sort_by_order(SortOrder::Descending);
+    }
+
+    /// Sorts `self[range]` ascending with the network for its length, leaving every element
+    /// outside the range untouched and unread.
+    ///
+    /// Panics if the range is out of bounds.
+    fn ct_sort_range(&mut self, range: Range<usize>);
+
+    /// Returns the permutation that sorts the slice, leaving the slice itself untouched. Each
+    /// element carries its original index through the network, and ties are broken by that
+    /// index, so the result is the unique stable sorting permutation.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_argsort(&self) -> Vec<u32>;
+
+    /// Returns the stable sorting permutation: equal elements keep their original relative
+    /// order. This is what [`ct_argsort`](Self::ct_argsort) returns already, since it breaks
+    /// ties by index; the separate name is for call sites that rely on the tie-breaking.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_argsort_stable(&self) -> Vec<u32>;
+
+    /// Sorts ascending, keeping equal elements in their original order. Each element is paired
+    /// with its index as a `u32`, the pairs are sorted by `(value, index)`, and the indices are
+    /// dropped again, so this costs a scratch buffer and wider comparisons.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_sort_stable(&mut self);
+
+    /// Like [`ct_sort_stable`](Self::ct_sort_stable), but returns [`SortError::TooLong`] instead
+    /// of panicking if the slice has more than `u32::MAX` elements. The check looks only at the
+    /// length, and a slice that fails it is left untouched.
+    #[cfg(feature = "alloc")]
+    fn try_ct_sort_stable(&mut self) -> Result<(), SortError>;
+
+    /// Sorts ascending and returns a mask with one entry per element that is `true` where the
+    /// element differs from its predecessor, marking the first of each run of equal values. The
+    /// mask is computed by comparing every adjacent pair in one pass.
+    #[cfg(feature = "alloc")]
+    fn ct_sort_with_unique_mask(&mut self) -> Vec<bool>;
+
+    /// Sorts the slice ascending and applies the same permutation to `values`, mirroring every
+    /// compare-exchange on the keys with a conditional swap on the values.
+    ///
+    /// Panics if `values` differs in length from the slice.
+    fn ct_sort_with<V: ConditionalSwap>(&mut self, values: &mut [V]);
+
+    /// Runs the compare-exchanges `net` in order, each one moving the smaller element to its
+    /// first index. With the output of [`build_network`] for this length, the result is identical
+    /// to [`ct_sort`](Self::ct_sort).
+    ///
+    /// Panics if an index is out of bounds.
+    fn apply_network(&mut self, net: &[(usize, usize)]);
+
+    /// Sorts ascending with Batcher's bitonic network instead of the default one. The result is
+    /// the same; the network uses more comparators but has a simpler, recursive access pattern.
+    fn ct_sort_bitonic(&mut self);
+
+    /// Sorts ascending with Batcher's odd-even merge sort instead of the default network, as an
+    /// independent cross-check. The result is the same.
+    fn ct_sort_oddeven(&mut self);
+}
+
+/// Implements every method of [`ConstantTimeSort`] by delegating to the slice impl, for
+/// containers whose `[..]` is a slice.
+macro_rules! forward_constant_time_sort {
+    () => {
+        fn ct_sort_by_order(&mut self, order: SortOrder) {
+            self[..].ct_sort_by_order(order);
+        }
+
+        fn ct_sort_range(&mut self, range: Range<usize>) {
+            self[..].ct_sort_range(range);
+        }
+
+        #[cfg(feature = "alloc")]
+        fn ct_argsort(&self) -> Vec<u32> {
+            self[..].ct_argsort()
+        }
+
+        #[cfg(feature = "alloc")]
+        fn ct_argsort_stable(&self) -> Vec<u32> {
+            self[..].ct_argsort_stable()
+        }
+
+        #[cfg(feature = "alloc")]
+        fn ct_sort_stable(&mut self) {
+            self[..].ct_sort_stable();
+        }
+
+        #[cfg(feature = "alloc")]
+        fn try_ct_sort_stable(&mut self) -> Result<(), SortError> {
+            self[..].try_ct_sort_stable()
+        }
+
+        #[cfg(feature = "alloc")]
+        fn ct_sort_with_unique_mask(&mut self) -> Vec<bool> {
+            self[..].ct_sort_with_unique_mask()
+        }
+
+        fn ct_sort_with<V: ConditionalSwap>(&mut self, values: &mut [V]) {
+            self[..].ct_sort_with(values);
+        }
+
+        fn apply_network(&mut self, net: &[(usize, usize)]) {
+            self[..].apply_network(net);
+        }
+
+        fn ct_sort_bitonic(&mut self) {
+            self[..].ct_sort_bitonic();
+        }
+
+        fn ct_sort_oddeven(&mut self) {
+            self[..].ct_sort_oddeven();
+        }
+    };
+}
+
+/// Sorts arrays without slicing them first. Every method delegates to the slice impl.
+///
+/// ```
+/// use djbsort::ConstantTimeSort;
+///
+/// let mut a = [3u32, 1, 2];
+/// a.ct_sort();
+/// assert_eq!(a, [1, 2, 3]);
+/// ```
+impl<T, const N: usize> ConstantTimeSort for [T; N]
+where
+    [T]: ConstantTimeSort,
+{
+    forward_constant_time_sort!();
+}
+
+/// Lets generic code that takes `S: ConstantTimeSort` accept a `Vec` directly. Method calls such
+/// as `vec.ct_sort()` resolve here first and then run the slice impl, so the result is the same
+/// as for `vec[..]`.
+#[cfg(feature = "alloc")]
+impl<T> ConstantTimeSort for Vec<T>
+where
+    [T]: ConstantTimeSort,
+{
+    forward_constant_time_sort!();
+}
+
+/// Calls `compare_exchange(i, j)`, with `i < j`, for every comparator of the sorting network for
+/// `len` elements, in execution order. The sequence depends only on `len`.
+#[inline(always)]
+fn network<F: FnMut(usize, usize)>(len: usize, mut compare_exchange: F) {
+    network_runs(len, |lo, hi, count| {
+        for k in 0..count {
+            compare_exchange(lo + k, hi + k);
+        }
+    });
+}
+
+/// Returns the largest power of two below `len`, which is the widest stride of the network, or
+/// `None` if there are fewer than two elements.
+#[inline(always)]
+fn top(len: usize) -> Option<usize> {
+    (len > 1).then(|| 1 << (usize::BITS - 1 - (len - 1).leading_zeros()))
+}
+
+/// Calls `run(lo, hi, count)` for every run of the sorting network for `len` elements, in
+/// execution order. A run stands for the `count` comparators `(lo + k, hi + k)`, which touch
+/// disjoint elements and may therefore be applied in any order, or all at once. The sequence
+/// depends only on `len`. Always inlined, so that for fixed-size arrays every loop bound is a
+/// compile-time constant.
+///
+/// For 9 to 16 elements, the network with the fewest known comparators replaces the generic one,
+/// each of its comparators forming a run of its own. From 17 to 32 elements, the runs of the
+/// generic network come from a table built at compile time. Otherwise this is
+/// [`generic_network_runs`].
+#[inline(always)]
+fn network_runs<F: FnMut(usize, usize, usize)>(len: usize, mut run: F) {
+    if let Some(pairs) = small::optimal_network(len) {
+        pairs.iter().for_each(|&(i, j)| run(i, j, 1));
+    } else if let Some(runs) = small::precomputed_runs(len) {
+        runs.iter().for_each(|&(lo, hi, count)| run(lo, hi, count));
+    } else {
+        generic_network_runs(len, run);
+    }
+}
+
+/// Calls `run(lo, hi, count)` for every run of djbsort's network for `len` elements, which
+/// [`network_runs`] uses for every length without a smaller network. Powers of two take a
+/// shorter path that yields the same runs; the choice depends only on `len`.
+#[inline(always)]
+fn generic_network_runs<F: FnMut(usize, usize, usize)>(len: usize, run: F) {
+    if len.is_power_of_two() {
+        power_of_two_network_runs(len, run);
+    } else {
+        general_network_runs(len, run);
+    }
+}
+
+/// Calls `run(lo, hi, count)` for every run of djbsort's network for any `len`.
+///
+/// The layers of each merge step are already fused block by block. Fusing across steps is not
+/// possible: every step opens with comparators of stride up to `top`, which depend on the whole
+/// previous step, so no block smaller than the slice can be carried further on its own.
+#[inline(always)]
+fn general_network_runs<F: FnMut(usize, usize, usize)>(len: usize, mut run: F) {
+    use core::iter::successors;
+
+    if let Some(top) = top(len) {
+        for p in successors(Some(top), |p| Some(p.wrapping_shr(1))).take_while(|p| *p > 0) {
+            stride_runs(0, len - p, p, |start, count| run(start, start + p, count));
+            successors(Some(top), |t| Some(t.wrapping_shr(1)))
+                .take_while(|q| *q > p)
+                .fold(0usize, |offset, q| {
+                    stride_runs(offset, len - q, p, |start, count| {
+                        successors(Some(q), |r| Some(r.wrapping_shr(1)))
+                            .take_while(|r| *r > p)
+                            .for_each(|r| run(start + p, start + r, count));
+                    });
+                    len - q
+                });
+        }
+    }
+}
+
+/// Calls the same runs as [`general_network_runs`] for a `len` that is a power of two. Every
+/// block of `2p` elements then lies wholly inside the slice and every offset is a multiple of
+/// `2p`, so each run covers exactly `p` comparators and the clipping at the ends disappears.
+#[inline(always)]
+fn power_of_two_network_runs<F: FnMut(usize, usize, usize)>(len: usize, mut run: F) {
+    let mut p = len / 2;
+    while p > 0 {
+        for block in (0..len - p).step_by(2 * p) {
+            run(block, block + p, p);
+        }
+        let mut q = len / 2;
+        let mut offset = 0;
+        while q > p {
+            for block in (offset..len - q).step_by(2 * p) {
+                let mut r = q;
+                while r > p {
+                    run(block + p, block + r, p);
+                    r /= 2;
+                }
+            }
+            offset = len - q;
+            q /= 2;
+        }
+        p /= 2;
+    }
+}
+
+/// Returns the number of compare-exchanges [`ConstantTimeSort::ct_sort`] performs on a slice of
+/// `len` elements. Only the runs of the network are enumerated, so this is much cheaper than
+/// sorting. It is a `const fn`, so it can size the array for [`network_array`].
+pub const fn network_comparators(len: usize) -> usize {
+    const_network::<0>(len).1
+}
+
+/// Returns the comparators of the sorting network for `N` elements, in execution order, where
+/// `M` must be `network_comparators(N)`. Evaluated in a constant, the network is generated at
+/// compile time and [`ConstantTimeSort::apply_network`] on a `[T; N]` has only constant indices
+/// left to work with:
+///
+/// ```
+/// use djbsort::{network_array, network_comparators, ConstantTimeSort};
+///
+/// const NETWORK: [(usize, usize); network_comparators(8)] =
+///     network_array::<8, { network_comparators(8) }>();
+///
+/// let mut array = [5u32, 3, 7, 1, 0, 6, 2, 4];
+/// array.apply_network(&NETWORK);
+/// assert_eq!(array, [0, 1, 2, 3, 4, 5, 6, 7]);
+/// ```
+///
+/// Panics, or fails to compile in a constant, if `M` is not `network_comparators(N)`.
+pub const fn network_array<const N: usize, const M: usize>() -> [(usize, usize); M] {
+    let (pairs, count) = const_network::<M>(N);
+    assert!(count == M, "M must equal network_comparators(N)");
+    pairs
+}
+
+/// Returns the comparators of the sorting network for `len` elements, as [`network_runs`] issues
+/// them, storing the first `M` and counting all of them.
+const fn const_network<const M: usize>(len: usize) -> ([(usize, usize); M], usize) {
+    let (runs, count) = const_walk::<M>(len, false);
+    let mut pairs = [(0, 0); M];
+    let mut k = 0;
+    while k < M {
+        pairs[k] = (runs[k].0, runs[k].1);
+        k += 1;
+    }
+    (pairs, count)
+}
+
+/// Returns the number of runs [`network_runs`] issues for `len` elements.
+const fn network_run_count(len: usize) -> usize {
+    const_walk::<0>(len, true).1
+}
+
+/// Returns the runs `(lo, hi, count)` that [`network_runs`] issues for `len` elements, where `M`
+/// must be `network_run_count(len)`.
+const fn network_run_array<const M: usize>(len: usize) -> [(usize, usize, usize); M] {
+    let (runs, count) = const_walk::<M>(len, true);
+    assert!(count == M, "M must equal network_run_count(len)");
+    runs
+}
+
+/// Walks the sorting network for `len` elements in the order of [`network_runs`] and stores the
+/// first `M` of its runs, or, unless `runs` is set, of its single comparators as runs of one.
+/// Returns them with the total number. Closures and iterators are not available in a `const fn`,
+/// so the loops are spelled out.
+const fn const_walk<const M: usize>(len: usize, runs: bool) -> ([(usize, usize, usize); M], usize) {
+    let mut out = [(0, 0, 0); M];
+    let mut count = 0;
+    if let Some(optimal) = small::optimal_network(len) {
+        while count < optimal.len() {
+            if count < M {
+                out[count] = (optimal[count].0, optimal[count].1, 1);
+            }
+            count += 1;
+        }
+        return (out, count);
+    }
+    if len < 2 {
+        return (out, count);
+    }
+    let top = 1 << (usize::BITS - 1 - (len - 1).leading_zeros());
+    let mut p = top;
+    while p > 0 {
+        count = const_stride_runs(&mut out, count, runs, 0, len - p, p, 0, p);
+        let mut offset = 0;
+        let mut q = top;
+        while q > p {
+            count = const_merge_runs(&mut out, count, runs, offset, len - q, p, q);
+            offset = len - q;
+            q >>= 1;
+        }
+        p >>= 1;
+    }
+    (out, count)
+}
+
+/// Stores the runs `(start + p, start + r, stop - start)` of every block of stride `p` in
+/// `begin..end`, for each `r` from `q` down to and excluding `p`, block by block.
+#[allow(clippy::too_many_arguments)]
+const fn const_merge_runs<const M: usize>(
+    out: &mut [(usize, usize, usize); M],
+    mut count: usize,
+    runs: bool,
+    begin: usize,
+    end: usize,
+    p: usize,
+    q: usize,
+) -> usize {
+    let mut block = begin & !(p | (p - 1));
+    while block < end {
+        let start = if block > begin { block } else { begin };
+        let stop = if block + p < end { block + p } else { end };
+        let mut r = q;
+        while r > p && start < stop {
+            count = const_run(out, count, runs, start + p, start + r, stop - start);
+            r >>= 1;
+        }
+        block = block.saturating_add(p).saturating_add(p);
+    }
+    count
+}
+
+/// Stores the runs `(start + lo, start + hi, stop - start)` of every block of stride `p` in
+/// `begin..end`.
+#[allow(clippy::too_many_arguments)]
+const fn const_stride_runs<const M: usize>(
+    out: &mut [(usize, usize, usize); M],
+    mut count: usize,
+    runs: bool,
+    begin: usize,
+    end: usize,
+    p: usize,
+    lo: usize,
+    hi: usize,
+) -> usize {
+    let mut block = begin & !(p | (p - 1));
+    while block < end {
+        let start = if block > begin { block } else { begin };
+        let stop = if block + p < end { block + p } else { end };
+        if start < stop {
+            count = const_run(out, count, runs, start + lo, start + hi, stop - start);
+        }
+        block = block.saturating_add(p).saturating_add(p);
+    }
+    count
+}
+
+/// Stores the run `(lo, hi, n)` at `out[count]`, or, unless `runs` is set, its comparators as
+/// runs of one at `out[count..]`, as far as they fit, and returns the new count.
+const fn const_run<const M: usize>(
+    out: &mut [(usize, usize, usize); M],
+    count: usize,
+    runs: bool,
+    lo: usize,
+    hi: usize,
+    n: usize,
+) -> usize {
+    if runs {
+        if count < M {
+            out[count] = (lo, hi, n);
+        }
+        return count + 1;
+    }
+    let mut k = 0;
+    while k < n && count + k < M {
+        out[count + k] = (lo + k, hi + k, 1);
+        k += 1;
+    }
+    count + n
+}
+
+/// Returns an iterator over the comparators `(i, j)`, with `i < j`, of the sorting network for
+/// `len` elements, in the order [`ConstantTimeSort::ct_sort`] applies them. The pairs are
+/// generated lazily from `len` alone.
+pub fn network_pairs(len: usize) -> impl Iterator<Item = (usize, usize)> {
+    use core::iter::successors;
+
+    let halvings = |start: usize| successors(Some(start), |t| Some(t.wrapping_shr(1)));
+    let small = small::optimal_network(len);
+    let generic = top(len).filter(|_| small.is_none());
+    let small = small.into_iter().flatten().copied();
+    small.chain(generic.into_iter().flat_map(move |top| {
+        halvings(top).take_while(|p| *p > 0).flat_map(move |p| {
+            let stage = stride_run_iter(0, len - p, p)
+                .flat_map(move |(start, count)| (start..start + count).map(move |i| (i, i + p)));
+            let merges = halvings(top)
+                .take_while(move |q| *q > p)
+                .scan(0usize, move |offset, q| {
+                    let begin = core::mem::replace(offset, len - q);
+                    Some((begin, q))
+                })
+                .flat_map(move |(offset, q)| {
+                    stride_run_iter(offset, len - q, p).flat_map(move |(start, count)| {
+                        halvings(q)
+                            .take_while(move |r| *r > p)
+                            .flat_map(move |r| (start..start + count).map(move |i| (i + p, i + r)))
+                    })
+                });
+            stage.chain(merges)
+        })
+    }))
+}
+
+/// A compare-exchange of the sorting network: the smaller of the elements at `lo` and `hi` ends
+/// up at `lo`, with `lo < hi`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Comparator {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+/// Returns an iterator over the comparators of the sorting network for `len` elements, in the
+/// order [`ConstantTimeSort::ct_sort`] applies them. Like [`network_pairs`], it is lazy and needs
+/// no allocation.
+pub fn comparators(len: usize) -> impl Iterator<Item = Comparator> {
+    network_pairs(len).map(|(lo, hi)| Comparator { lo, hi })
+}
+
+/// Returns the comparators of the sorting network for `len` elements, in execution order, for
+/// use with [`ConstantTimeSort::apply_network`].
+#[cfg(feature = "alloc")]
+pub fn build_network(len: usize) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::with_capacity(network_comparators(len));
+    network(len, |i, j| pairs.push((i, j)));
+    pairs
+}
+
+/// A layer of the sorting network: the comparators `(i + lo, i + hi)` for every `i` in
+/// `begin..end` with `i & p == 0`. They touch pairwise disjoint elements.
+#[cfg(feature = "parallel")]
+#[derive(Clone, Copy)]
+struct Layer {
+    begin: usize,
+    end: usize,
+    p: usize,
+    lo: usize,
+    hi: usize,
+}
+
+/// Calls `f(layer)` for every layer of djbsort's network for `len` elements, in execution order.
+/// Running the layers one after another is equivalent to [`generic_network_runs`], which merely
+/// interleaves the layers of each merge step block by block for locality. Only lengths far above
+/// those of the small networks are sorted in parallel, so this always matches [`network_runs`].
+#[cfg(feature = "parallel")]
+fn network_layers<F: FnMut(Layer)>(len: usize, mut f: F) {
+    use core::iter::successors;
+
+    if let Some(top) = top(len) {
+        for p in successors(Some(top), |p| Some(p.wrapping_shr(1))).take_while(|p| *p > 0) {
+            f(Layer {
+                begin: 0,
+                end: len - p,
+                p,
+                lo: 0,
+                hi: p,
+            });
+            successors(Some(top), |t| Some(t.wrapping_shr(1)))
+                .take_while(|q| *q > p)
+                .fold(0usize, |offset, q| {
+                    successors(Some(q), |r| Some(r.wrapping_shr(1)))
+                        .take_while(|r| *r > p)
+                        .for_each(|r| {
+                            f(Layer {
+                                begin: offset,
+                                end: len - q,
+                                p,
+                                lo: p,
+                                hi: r,
+                            })
+                        });
+                    len - q
+                });
+        }
+    }
+}
+
+/// Iterator form of [`stride_runs`], yielding `(start, count)`.
+fn stride_run_iter(begin: usize, end: usize, p: usize) -> impl Iterator<Item = (usize, usize)> {
+    core::iter::successors(Some(begin & !(p | (p - 1))), move |block| {
+        Some(block.saturating_add(p).saturating_add(p))
+    })
+    .take_while(move |block| *block < end)
+    .filter_map(move |block| {
+        let start = block.max(begin);
+        let stop = (block + p).min(end);
+        (start < stop).then(|| (start, stop - start))
+    })
+}
+
+/// Calls `f(start, count)` for every maximal run of consecutive `i` in `begin..end` with
+/// `i & p == 0`, where `p` is a power of two.
+///
+/// Every caller passes `p <= top < len` and `end <= len - p`, so that `start + p` and
+/// `block + p` stay below `len`. `p` may be as large as `2^(usize::BITS - 1)`, so the block size
+/// `2 * p` is never formed directly.
+#[inline(always)]
+fn stride_runs<F: FnMut(usize, usize)>(begin: usize, end: usize, p: usize, mut f: F) {
+    let mut block = begin & !(p | (p - 1));
+    while block < end {
+        let start = block.max(begin);
+        let stop = (block + p).min(end);
+        if start < stop {
+            f(start, stop - start);
+        }
+        block = block.saturating_add(p).saturating_add(p);
+    }
+}
+
+impl<T: CtOrd> ConstantTimeSort for [T] {
+    fn ct_sort_by_order(&mut self, order: SortOrder) {
+        // A descending run is an ascending one with the roles of its halves exchanged.
+        match order {
+            SortOrder::Ascending => network_runs(self.len(), |lo, hi, count| {
+                T::compare_exchange_run(self, lo, hi, count)
+            }),
+            SortOrder::Descending => network_runs(self.len(), |lo, hi, count| {
+                T::compare_exchange_run(self, hi, lo, count)
+            }),
+        }
+    }
+
+    fn ct_sort_range(&mut self, range: Range<usize>) {
+        self[range].ct_sort();
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_argsort(&self) -> Vec<u32> {
+        assert!(
+            self.len() <= u32::MAX as usize,
+            "slice too long for u32 indices"
+        );
+        let mut pairs: Scratch<(T, u32)> = self.iter().copied().zip(0..).collect();
+        network(pairs.len(), |i, j| {
+            let (a, ia) = pairs[i];
+            let (b, ib) = pairs[j];
+            let swap = a.ct_gt_mask(b) | (a.ct_eq_mask(b) & ia.ct_gt_mask(ib));
+            conditional_swap_at(&mut pairs, i, j, swap);
+        });
+        pairs.iter().map(|&(_, index)| index).collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_argsort_stable(&self) -> Vec<u32> {
+        self.ct_argsort()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_sort_stable(&mut self) {
+        if let Err(error) = self.try_ct_sort_stable() {
+            panic!("{}", error);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn try_ct_sort_stable(&mut self) -> Result<(), SortError> {
+        if self.len() > u32::MAX as usize {
+            return Err(SortError::TooLong {
+                max: u32::MAX as usize,
+            });
+        }
+        let mut pairs: Scratch<(T, u32)> = self.iter().copied().zip(0..).collect();
+        pairs.ct_sort();
+        for (dst, &(src, _)) in self.iter_mut().zip(pairs.iter()) {
+            *dst = src;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_sort_with_unique_mask(&mut self) -> Vec<bool> {
+        self.ct_sort();
+        let mut previous = None;
+        self.iter()
+            .map(|&x| {
+                let first = previous.map_or(Mask::TRUE, |p| !x.ct_eq_mask(p));
+                previous = Some(x);
+                first.into()
+            })
+            .collect()
+    }
+
+    fn ct_sort_with<V: ConditionalSwap>(&mut self, values: &mut [V]) {
+        assert_eq!(self.len(), values.len(), "keys and values differ in length");
+        network(self.len(), |i, j| {
+            let swap = self[i].ct_gt_mask(self[j]);
+            conditional_swap_at(self, i, j, swap);
+            conditional_swap_at(values, i, j, swap);
+        });
+    }
+
+    fn apply_network(&mut self, net: &[(usize, usize)]) {
+        for &(i, j) in net {
+            compare_exchange_at(self, i, j);
+        }
+    }
+
+    fn ct_sort_bitonic(&mut self) {
+        batcher::bitonic_network(self.len(), |i, j| compare_exchange_at(self, i, j));
+    }
+
+    fn ct_sort_oddeven(&mut self) {
+        batcher::odd_even_network(self.len(), |i, j| compare_exchange_at(self, i, j));
+    }
+}
+
+/// The outcome of a constant-time comparison: all ones for true and all zeros for false. The
+/// bitwise operators combine masks without branching; converting to `bool` is only safe once the
+/// result is no longer secret.
+///
+/// Every `Mask` is either `0x00` or `0xFF`: the field is private, and each constructor and
+/// operator preserves this. The `unsafe` [`ConditionalSwap`] impls rely on it, since a mixed mask
+/// would combine the bits of two values into one that need not be valid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mask(u8);
+
+impl Mask {
+    pub const TRUE: Mask = Mask(!0);
+    pub const FALSE: Mask = Mask(0);
+
+    /// Converts `b` into a mask without branching on it.
+    #[inline(always)]
+    pub fn from_bool(b: bool) -> Mask {
+        Mask((b as u8).wrapping_neg())
+    }
+
+    /// Converts a bit, `0` or `1`, into a mask, as when importing a `subtle::Choice` via
+    /// `unwrap_u8`. Only the lowest bit of `bit` is used, so any other value still gives
+    /// [`Mask::TRUE`] or [`Mask::FALSE`].
+    #[inline(always)]
+    pub fn from_bit(bit: u8) -> Mask {
+        Mask((bit & 1).wrapping_neg())
+    }
+
+    /// Returns `1` for [`Mask::TRUE`] and `0` for [`Mask::FALSE`], the form in which
+    /// `subtle::Choice::from` expects a condition.
+    #[inline(always)]
+    pub fn bit(self) -> u8 {
+        self.0 & 1
+    }
+}
+
+impl From<Mask> for bool {
+    #[inline(always)]
+    fn from(mask: Mask) -> bool {
+        mask.0 != 0
+    }
+}
+
+impl BitAnd for Mask {
+    type Output = Mask;
+
+    #[inline(always)]
+    fn bitand(self, other: Mask) -> Mask {
+        Mask(self.0 & other.0)
+    }
+}
+
+impl BitOr for Mask {
+    type Output = Mask;
+
+    #[inline(always)]
+    fn bitor(self, other: Mask) -> Mask {
+        Mask(self.0 | other.0)
+    }
+}
+
+impl Not for Mask {
+    type Output = Mask;
+
+    #[inline(always)]
+    fn not(self) -> Mask {
+        Mask(!self.0)
+    }
+}
+
+/// Constant-time total order. Implementing this for a type makes slices of it sortable with
+/// [`ConstantTimeSort`].
+///
+/// This is the extension point for types outside the crate, such as the scalars and field
+/// elements of a cryptography crate; the integer impls are the reference implementations. Only
+/// [`ct_gt_mask`](Self::ct_gt_mask) and [`ConditionalSwap`] are required:
+///
+/// ```
+/// use djbsort::{ConditionalSwap, ConstantTimeSort, CtOrd, Mask};
+///
+/// /// A field element as four little-endian 64-bit limbs.
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct FieldElement([u64; 4]);
+///
+/// impl ConditionalSwap for FieldElement {
+///     fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+///         <[u64; 4]>::conditional_swap(&mut a.0, &mut b.0, mask);
+///     }
+/// }
+///
+/// impl CtOrd for FieldElement {
+///     fn ct_gt_mask(self, other: Self) -> Mask {
+///         // Going up from the least significant limb, each limb decides unless it is equal.
+///         let mut gt = Mask::FALSE;
+///         for (a, b) in self.0.iter().zip(&other.0) {
+///             gt = a.ct_gt_mask(*b) | (a.ct_eq_mask(*b) & gt);
+///         }
+///         gt
+///     }
+/// }
+///
+/// let mut elements = [
+///     FieldElement([0, 0, 0, 1]),
+///     FieldElement([7, 0, 0, 0]),
+///     FieldElement([0, 1, 0, 0]),
+/// ];
+/// elements.ct_sort();
+/// assert_eq!(
+///     elements,
+///     [FieldElement([7, 0, 0, 0]), FieldElement([0, 1, 0, 0]), FieldElement([0, 0, 0, 1])]
+/// );
+/// ```
+pub trait CtOrd: ConditionalSwap + Copy {
+    /// Returns [`Mask::TRUE`] if `self > other` and [`Mask::FALSE`] otherwise, without branching
+    /// on either operand.
+    fn ct_gt_mask(self, other: Self) -> Mask;
+
+    /// Returns [`Mask::TRUE`] if `self == other` and [`Mask::FALSE`] otherwise.
+    #[inline(always)]
+    fn ct_eq_mask(self, other: Self) -> Mask {
+        !(self.ct_gt_mask(other) | other.ct_gt_mask(self))
+    }
+
+    /// Orders `(slice[lo + k], slice[hi + k])` for every `k < count`, so that the smaller value
+    /// ends up at `lo + k`. The two ranges must not overlap, but `lo` may exceed `hi`. Overridden
+    /// where a vectorized kernel exists.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn compare_exchange_run(slice: &mut [Self], lo: usize, hi: usize, count: usize) {
+        let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+        compare_exchange_scalar(lows, highs);
+    }
+}
+
+/// A record that sorts by one integer field. Implementing it does not sort anything by itself:
+/// with the `derive` feature, `#[derive(FixedWidthKey)]` implements this trait together with
+/// [`CtOrd`], ordering by the field marked `#[sort_key]`, and [`ConditionalSwap`], exchanging
+/// every field, so that `[Record]::ct_sort` works directly.
+///
+/// The struct must also be `Copy`, and cannot have generic parameters. Records with equal keys
+/// end up in an unspecified order; see
+/// [`ct_sort_stable`](ConstantTimeSort::ct_sort_stable) to keep them as they were.
+///
+#[cfg_attr(feature = "derive", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use djbsort::{ConstantTimeSort, FixedWidthKey};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, FixedWidthKey)]
+/// struct Entry {
+///     #[sort_key]
+///     priority: u32,
+///     payload: [u8; 8],
+/// }
+///
+/// let mut entries = [
+///     Entry { priority: 7, payload: [1; 8] },
+///     Entry { priority: 2, payload: [2; 8] },
+/// ];
+/// entries.ct_sort();
+/// assert_eq!(entries[0], Entry { priority: 2, payload: [2; 8] });
+/// assert_eq!(entries[1].key(), 7);
+/// ```
+pub trait FixedWidthKey {
+    /// The type of the key, one of the primitive integers.
+    type Key: CtOrd + GreaterThanMask;
+
+    /// Returns the key to sort by.
+    fn key(&self) -> Self::Key;
+}
+
+macro_rules! generate_ct_ord {
+    ($t:ty) => {
+        impl CtOrd for $t {
+            #[inline(always)]
+            fn ct_gt_mask(self, other: Self) -> Mask {
+                Mask(self.gt_mask(other) as u8)
+            }
+        }
+    };
+    ($t:ty, $run:path) => {
+        impl CtOrd for $t {
+            #[inline(always)]
+            fn ct_gt_mask(self, other: Self) -> Mask {
+                Mask(self.gt_mask(other) as u8)
+            }
+
+            #[inline(always)]
+            fn compare_exchange_run(slice: &mut [Self], lo: usize, hi: usize, count: usize) {
+                $run(slice, lo, hi, count);
+            }
+        }
+    };
+}
+
+generate_ct_ord!(u8, simd::compare_exchange_run_u8);
+generate_ct_ord!(u16, simd::compare_exchange_run_u16);
+generate_ct_ord!(u32, simd::compare_exchange_run_u32);
+generate_ct_ord!(u64, simd::compare_exchange_run_u64);
+generate_ct_ord!(u128);
+generate_ct_ord!(usize);
+generate_ct_ord!(i8);
+generate_ct_ord!(i16);
+generate_ct_ord!(i32);
+generate_ct_ord!(i64);
+generate_ct_ord!(i128);
+generate_ct_ord!(isize);
+
+impl CtOrd for char {
+    /// Orders by scalar value. Every `char` fits in 21 bits, so the `u32` comparison is exact.
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        (self as u32).ct_gt_mask(other as u32)
+    }
+}
+
+impl<const N: usize> CtOrd for [u8; N] {
+    /// Orders lexicographically, i.e. as big-endian `N`-byte integers. Up to 16 bytes are packed
+    /// into a `u128`; longer keys are compared byte by byte from the last to the first, each
+    /// byte deciding only if every earlier one is equal.
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        if N <= 16 {
+            let pack = |bytes: Self| bytes.iter().fold(0u128, |acc, &b| acc << 8 | b as u128);
+            pack(self).ct_gt_mask(pack(other))
+        } else {
+            self.iter()
+                .zip(&other)
+                .rev()
+                .fold(Mask::FALSE, |gt, (&a, &b)| {
+                    a.ct_gt_mask(b) | (a.ct_eq_mask(b) & gt)
+                })
+        }
+    }
+}
+
+impl<A: CtOrd, B: CtOrd> CtOrd for (A, B) {
+    /// Orders lexicographically: by the first element, with ties broken by the second.
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        self.0.ct_gt_mask(other.0) | (self.0.ct_eq_mask(other.0) & self.1.ct_gt_mask(other.1))
+    }
+}
+
+macro_rules! generate_float_ct_ord {
+    ($f:ty, $u:ty, $i:ty) => {
+        impl CtOrd for $f {
+            #[doc = concat!("Orders by [`total_cmp`](", stringify!($f), "::total_cmp): the bits are")]
+            /// mapped to an unsigned key by flipping the sign bit of non-negative values and every
+            /// bit of negative ones. NaNs with the sign bit clear sort after `∞` and those with it
+            /// set before `-∞`, and `-0.0` sorts before `0.0`.
+            #[inline(always)]
+            fn ct_gt_mask(self, other: Self) -> Mask {
+                let key = |x: $f| {
+                    let bits = x.to_bits();
+                    bits ^ (((bits as $i) >> (<$u>::BITS - 1)) as $u | 1 << (<$u>::BITS - 1))
+                };
+                key(self).ct_gt_mask(key(other))
+            }
+        }
+
+        impl ConditionalSwap for $f {
+            #[inline(always)]
+            fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+                let (mut x, mut y) = (a.to_bits(), b.to_bits());
+                <$u>::conditional_swap(&mut x, &mut y, mask);
+                *a = <$f>::from_bits(x);
+                *b = <$f>::from_bits(y);
+            }
+        }
+    };
+}
+
+generate_float_ct_ord!(f32, u32, i32);
+generate_float_ct_ord!(f64, u64, i64);
+
+macro_rules! generate_nonzero {
+    ($nz:ty, $t:ty) => {
+        impl CtOrd for $nz {
+            #[inline(always)]
+            fn ct_gt_mask(self, other: Self) -> Mask {
+                self.get().ct_gt_mask(other.get())
+            }
+        }
+
+        impl ConditionalSwap for $nz {
+            #[inline(always)]
+            fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+                let (mut x, mut y) = (a.get(), b.get());
+                <$t>::conditional_swap(&mut x, &mut y, mask);
+                // SAFETY: every `Mask` is all ones or all zeros, so the values are either
+                // swapped or kept, and both are still non-zero.
+                unsafe {
+                    *a = <$nz>::new_unchecked(x);
+                    *b = <$nz>::new_unchecked(y);
+                }
+            }
+        }
+    };
 }
 
-macro_rules! generate_constant_time_sort {
-    ($t:ty) => {
-        impl ConstantTimeSort for [$t] {
-            fn ct_sort(&mut self) {
-                use std::iter::successors;
-
-                if let Some(top) = successors(Some(1usize), |t| t.checked_mul(2))
-                    .take_while(|t| *t < self.len())
-                    .last()
-                {
-                    for p in
-                        successors(Some(top), |p| Some(p.wrapping_shr(1))).take_while(|p| *p > 0)
-                    {
-                        for i in 0..(self.len() - p) {
-                            if i & p == 0 {
-                                self.minmax_at(i, i + p);
-                            }
-                        }
-                        successors(Some(top), |t| Some(t.wrapping_shr(1)))
-                            .take_while(|q| *q > p)
-                            .fold(0usize, |offset, q| {
-                                for i in offset..(self.len() - q) {
-                                    if i & p == 0 {
-                                        self[i + p] =
-                                            successors(Some(q), |r| Some(r.wrapping_shr(1)))
-                                                .take_while(|r| *r > p)
-                                                .fold(self[i + p], |a, r| self.minmax(a, i + r));
-                                    }
-                                }
-                                self.len() - q
-                            });
-                    }
+generate_nonzero!(core::num::NonZeroU8, u8);
+generate_nonzero!(core::num::NonZeroU16, u16);
+generate_nonzero!(core::num::NonZeroU32, u32);
+generate_nonzero!(core::num::NonZeroU64, u64);
+generate_nonzero!(core::num::NonZeroU128, u128);
+generate_nonzero!(core::num::NonZeroUsize, usize);
+generate_nonzero!(core::num::NonZeroI8, i8);
+generate_nonzero!(core::num::NonZeroI16, i16);
+generate_nonzero!(core::num::NonZeroI32, i32);
+generate_nonzero!(core::num::NonZeroI64, i64);
+generate_nonzero!(core::num::NonZeroI128, i128);
+generate_nonzero!(core::num::NonZeroIsize, isize);
+
+impl<T: CtOrd> CtOrd for core::num::Wrapping<T> {
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        self.0.ct_gt_mask(other.0)
+    }
+}
+
+impl<T: ConditionalSwap> ConditionalSwap for core::num::Wrapping<T> {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        T::conditional_swap(&mut a.0, &mut b.0, mask);
+    }
+}
+
+impl<T: CtOrd> CtOrd for core::cmp::Reverse<T> {
+    /// Orders opposite to `T`, so that sorting a slice of `Reverse`s sorts their contents
+    /// descending.
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        other.0.ct_gt_mask(self.0)
+    }
+}
+
+impl<T: ConditionalSwap> ConditionalSwap for core::cmp::Reverse<T> {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        T::conditional_swap(&mut a.0, &mut b.0, mask);
+    }
+}
+
+impl CtOrd for bool {
+    /// `false < true`, comparing the bools as the integers `0` and `1`.
+    #[inline(always)]
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        (self as u8).ct_gt_mask(other as u8)
+    }
+}
+
+/// Swaps `a` and `b` if `mask` is all ones and leaves them untouched if it is `0`, without
+/// branching on `mask`. Any other value of `mask` mixes the bits of `a` and `b`.
+///
+/// The mask passes through [`black_box`](core::hint::black_box), so the optimizer cannot prove
+/// that it is either `0` or all ones and turn the swap back into a branch.
+#[inline(always)]
+pub fn ct_cswap<T>(a: &mut T, b: &mut T, mask: T)
+where
+    T: Copy + BitAnd<Output = T> + BitXor<Output = T>,
+{
+    let swap_operator = (*a ^ *b) & core::hint::black_box(mask);
+    *a = *a ^ swap_operator;
+    *b = *b ^ swap_operator;
+}
+
+/// Returns `a` if `mask` is all ones and `b` if it is `0`, as `b ^ (mask & (a ^ b))`, without
+/// branching on `mask`. Any other value of `mask` mixes the bits of `a` and `b`.
+///
+/// As in [`ct_cswap`], the mask passes through [`black_box`](core::hint::black_box).
+#[inline(always)]
+pub fn select_mask<T>(mask: T, a: T, b: T) -> T
+where
+    T: Copy + BitAnd<Output = T> + BitXor<Output = T>,
+{
+    b ^ (core::hint::black_box(mask) & (a ^ b))
+}
+
+pub trait ConditionalSwap {
+    /// Swaps `a` and `b` if `mask` is [`Mask::TRUE`] and leaves them untouched if it is
+    /// [`Mask::FALSE`], without branching on `mask`.
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask);
+}
+
+macro_rules! generate_conditional_swap {
+    ($t:ty) => {
+        impl ConditionalSwap for $t {
+            #[inline(always)]
+            fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+                // Sign extension widens the byte mask to the full width.
+                ct_cswap(a, b, mask.0 as i8 as $t);
+            }
+        }
+    };
+}
+
+generate_conditional_swap!(u8);
+generate_conditional_swap!(u16);
+generate_conditional_swap!(u32);
+generate_conditional_swap!(u64);
+generate_conditional_swap!(u128);
+generate_conditional_swap!(usize);
+generate_conditional_swap!(i8);
+generate_conditional_swap!(i16);
+generate_conditional_swap!(i32);
+generate_conditional_swap!(i64);
+generate_conditional_swap!(i128);
+generate_conditional_swap!(isize);
+
+impl ConditionalSwap for bool {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        // `as u8` maps `true` to exactly `1`, so the swapped bytes are again `0` or `1`.
+        let (mut x, mut y) = (*a as u8, *b as u8);
+        u8::conditional_swap(&mut x, &mut y, mask);
+        *a = x != 0;
+        *b = y != 0;
+    }
+}
+
+impl ConditionalSwap for char {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        let (mut x, mut y) = (*a as u32, *b as u32);
+        u32::conditional_swap(&mut x, &mut y, mask);
+        // SAFETY: every `Mask` is full or empty; a full mask swaps the two code points and an
+        // empty one keeps them, so both are still valid `char`s.
+        unsafe {
+            *a = char::from_u32_unchecked(x);
+            *b = char::from_u32_unchecked(y);
+        }
+    }
+}
+
+impl<T: ConditionalSwap, const N: usize> ConditionalSwap for [T; N] {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        for (x, y) in a.iter_mut().zip(b) {
+            T::conditional_swap(x, y, mask);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ConditionalSwap for Vec<T> {
+    /// Swaps the two vectors' pointers, lengths and capacities, never their elements.
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        use core::mem::{take, ManuallyDrop};
+
+        let (mut x, mut y) = (ManuallyDrop::new(take(a)), ManuallyDrop::new(take(b)));
+        let (mut x_ptr, mut y_ptr) = (x.as_mut_ptr() as usize, y.as_mut_ptr() as usize);
+        let (mut x_len, mut y_len) = (x.len(), y.len());
+        let (mut x_cap, mut y_cap) = (x.capacity(), y.capacity());
+        usize::conditional_swap(&mut x_ptr, &mut y_ptr, mask);
+        usize::conditional_swap(&mut x_len, &mut y_len, mask);
+        usize::conditional_swap(&mut x_cap, &mut y_cap, mask);
+        // SAFETY: every `Mask` is all ones or all zeros, so all three parts were swapped or kept
+        // together, and each triple describes one of the original vectors, whose ownership moves
+        // into exactly one new vector.
+        unsafe {
+            *a = Vec::from_raw_parts(x_ptr as *mut T, x_len, x_cap);
+            *b = Vec::from_raw_parts(y_ptr as *mut T, y_len, y_cap);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ConditionalSwap for alloc::string::String {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        let (mut x, mut y) = (
+            core::mem::take(a).into_bytes(),
+            core::mem::take(b).into_bytes(),
+        );
+        Vec::conditional_swap(&mut x, &mut y, mask);
+        // SAFETY: each buffer is one of the original strings, which were valid UTF-8.
+        unsafe {
+            *a = alloc::string::String::from_utf8_unchecked(x);
+            *b = alloc::string::String::from_utf8_unchecked(y);
+        }
+    }
+}
+
+impl<A: ConditionalSwap, B: ConditionalSwap> ConditionalSwap for (A, B) {
+    #[inline(always)]
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        A::conditional_swap(&mut a.0, &mut b.0, mask);
+        B::conditional_swap(&mut a.1, &mut b.1, mask);
+    }
+}
+
+#[inline(always)]
+fn conditional_swap_at<T: ConditionalSwap>(slice: &mut [T], i: usize, j: usize, mask: Mask) {
+    let (head, tail) = slice.split_at_mut(j);
+    T::conditional_swap(&mut head[i], &mut tail[0], mask);
+}
+
+/// Reverses `slice` if `cond` is true and leaves it as it is otherwise, without branching on
+/// `cond`: every mirrored pair `(i, len - 1 - i)` goes through
+/// [`conditional_swap`](ConditionalSwap::conditional_swap) with the same mask, so the same
+/// elements are read and written either way. For a secret condition held as a `subtle::Choice`,
+/// pass `bool::from(choice)`, or build the [`Mask`] with [`Mask::from_bit`] and swap directly.
+pub fn ct_conditional_reverse<T: ConditionalSwap>(slice: &mut [T], cond: bool) {
+    let mask = Mask::from_bool(cond);
+    let (front, back) = slice.split_at_mut(slice.len() / 2);
+    for (a, b) in front.iter_mut().zip(back.iter_mut().rev()) {
+        T::conditional_swap(a, b, mask);
+    }
+}
+
+/// Orders `(lows[k], highs[k])` for every `k`, so that the smaller value ends up in `lows`.
+/// Zipping the two halves of a run keeps bounds checks out of the loop.
+#[inline(always)]
+fn compare_exchange_scalar<T: CtOrd>(lows: &mut [T], highs: &mut [T]) {
+    for (a, b) in lows.iter_mut().zip(highs) {
+        let mask = a.ct_gt_mask(*b);
+        T::conditional_swap(a, b, mask);
+    }
+}
+
+/// Splits out the two non-overlapping ranges `lo..lo + count` and `hi..hi + count`.
+#[inline(always)]
+fn disjoint_runs<T>(slice: &mut [T], lo: usize, hi: usize, count: usize) -> (&mut [T], &mut [T]) {
+    if lo < hi {
+        let (head, tail) = slice.split_at_mut(hi);
+        (&mut head[lo..lo + count], &mut tail[..count])
+    } else {
+        let (head, tail) = slice.split_at_mut(lo);
+        (&mut tail[..count], &mut head[hi..hi + count])
+    }
+}
+
+/// Orders `slice[i]` and `slice[j]` so that the smaller value ends up at `i`.
+#[inline(always)]
+fn compare_exchange_at<T: CtOrd>(slice: &mut [T], i: usize, j: usize) {
+    let (mut a, mut b) = (slice[i], slice[j]);
+    let mask = a.ct_gt_mask(b);
+    T::conditional_swap(&mut a, &mut b, mask);
+    slice[i] = a;
+    slice[j] = b;
+}
+
+/// Branch-free greater-than comparison.
+pub trait GreaterThanMask {
+    /// Returns all ones (`T::MAX` for unsigned types, `-1` for signed ones) if `self > other`,
+    /// and `0` otherwise, without branching on either operand.
+    fn gt_mask(self, other: Self) -> Self;
+}
+
+/// Branch-free less-than comparison, the mirror image of [`GreaterThanMask`].
+pub trait LessThanMask {
+    /// Returns all ones if `self < other`, and `0` otherwise, without branching on either
+    /// operand. This is `other.gt_mask(self)`.
+    fn lt_mask(self, other: Self) -> Self;
+}
+
+impl<T: GreaterThanMask> LessThanMask for T {
+    #[inline(always)]
+    fn lt_mask(self, other: Self) -> Self {
+        other.gt_mask(self)
+    }
+}
+
+/// The unsigned integer operations the branch-free comparison is built from, with the width
+/// taken from the type itself.
+trait FixedWidth:
+    Copy + BitOr<Output = Self> + BitXor<Output = Self> + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+
+    fn wrapping_sub(self, other: Self) -> Self;
+
+    fn wrapping_neg(self) -> Self;
+}
+
+/// Evaluates the branch-free greater-than mask of two unsigned integers.
+#[inline(always)]
+fn gt_mask_inline<T: FixedWidth>(a: T, b: T) -> T {
+    let mut result = b.wrapping_sub(a);
+    result = result ^ a;
+    result = result | (b ^ a);
+    result = result ^ b;
+    result = result >> (T::BITS - 1);
+    result.wrapping_neg()
+}
+
+impl<T: FixedWidth> GreaterThanMask for T {
+    // Out of line, the mask reaches its callers as an opaque return value, which no optimizer
+    // can turn back into a branch. `aggressive-inline` trades that for speed.
+    #[cfg_attr(not(feature = "aggressive-inline"), inline(never))]
+    #[cfg_attr(feature = "aggressive-inline", inline(always))]
+    fn gt_mask(self, other: Self) -> Self {
+        gt_mask_inline(self, other)
+    }
+}
+
+/// Implements [`FixedWidth`] for an unsigned type, and [`GreaterThanMask`] for the signed type
+/// of the same width on top of it.
+macro_rules! generate_fixed_width {
+    ($i:ty, $u:ty) => {
+        impl FixedWidth for $u {
+            const BITS: u32 = <$u>::BITS;
+
+            #[inline(always)]
+            fn wrapping_sub(self, other: Self) -> Self {
+                <$u>::wrapping_sub(self, other)
+            }
+
+            #[inline(always)]
+            fn wrapping_neg(self) -> Self {
+                <$u>::wrapping_neg(self)
+            }
+        }
+
+        impl GreaterThanMask for $i {
+            #[inline(always)]
+            fn gt_mask(self, other: Self) -> Self {
+                // Flipping the sign bit maps the signed range monotonically onto the unsigned one.
+                let bias = <$i>::MIN as $u;
+                ((self as $u ^ bias).gt_mask(other as $u ^ bias)) as $i
+            }
+        }
+    };
+}
+
+generate_fixed_width!(i8, u8);
+generate_fixed_width!(i16, u16);
+generate_fixed_width!(i32, u32);
+generate_fixed_width!(i64, u64);
+generate_fixed_width!(i128, u128);
+generate_fixed_width!(isize, usize);
+
+/// Writes `a[k].gt_mask(b[k])` to `out[k]` for every `k`. Unlike
+/// [`gt_mask`](GreaterThanMask::gt_mask), which is kept out of line, the comparison is inlined
+/// into the loop, so the compiler can vectorize it.
+///
+/// Panics if the three slices differ in length.
+pub fn gt_mask_slice(a: &[u32], b: &[u32], out: &mut [u32]) {
+    assert!(
+        a.len() == out.len() && b.len() == out.len(),
+        "slices differ in length"
+    );
+    for ((out, &a), &b) in out.iter_mut().zip(a).zip(b) {
+        *out = gt_mask_inline(a, b);
+    }
+}
+
+/// Branch-free equality comparison.
+pub trait EqualMask {
+    /// Returns all ones if `self == other`, and `0` otherwise, without branching on either
+    /// operand. It is derived from [`gt_mask`](GreaterThanMask::gt_mask) in both directions.
+    fn eq_mask(self, other: Self) -> Self;
+}
+
+impl<T: GreaterThanMask + Copy + BitOr<Output = T> + Not<Output = T>> EqualMask for T {
+    #[inline(always)]
+    fn eq_mask(self, other: Self) -> Self {
+        !(self.gt_mask(other) | other.gt_mask(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[cfg(feature = "alloc")]
+    use crate::build_network;
+    use crate::ct_conditional_reverse;
+    use crate::ct_cswap;
+    use crate::gt_mask_slice;
+    use crate::network_array;
+    use crate::network_comparators;
+    use crate::network_pairs;
+    use crate::select_mask;
+    use crate::ConditionalSwap;
+    use crate::ConstantTimeSort;
+    use crate::CtOrd;
+    use crate::EqualMask;
+    use crate::GreaterThanMask;
+    use crate::LessThanMask;
+    use crate::Mask;
+    use crate::SortOrder;
+    use crate::{comparators, Comparator};
+
+    #[cfg(target_pointer_width = "32")]
+    type PointerWidth = u32;
+    #[cfg(target_pointer_width = "64")]
+    type PointerWidth = u64;
+
+    macro_rules! sort_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            sort_random!($name, $t, $t, $n);
+        };
+        ($name:ident, $t:ty, $sample:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng()
+                        .random_iter::<$sample>()
+                        .take(count)
+                        .map(|x| x as $t)
+                        .collect();
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort();
+                    expected.sort();
+                    assert_eq!(vec, expected);
+                }
+            }
+        };
+    }
+
+    sort_random!(sort_u8_random, u8, 1024);
+    sort_random!(sort_u16_random, u16, 1024);
+    sort_random!(sort_u32_random, u32, 1024);
+    sort_random!(sort_u64_random, u64, 1024);
+    sort_random!(sort_u128_random, u128, 1024);
+    sort_random!(sort_i8_random, i8, 1024);
+    sort_random!(sort_i16_random, i16, 1024);
+    sort_random!(sort_i32_random, i32, 1024);
+    sort_random!(sort_i64_random, i64, 1024);
+    sort_random!(sort_i128_random, i128, 1024);
+    sort_random!(sort_usize_random, usize, PointerWidth, 1024);
+    sort_random!(sort_isize_random, isize, PointerWidth, 1024);
+
+    #[test]
+    fn sort_usize_pointer_width() {
+        let high = 1usize << (usize::BITS - 1);
+        let mut vec = vec![usize::MAX, high, 0, high - 1, 1, high + 1];
+        vec.ct_sort();
+        assert_eq!(vec, [0, 1, high - 1, high, high + 1, usize::MAX]);
+
+        let mut vec = vec![isize::MAX, 0, isize::MIN, -1, 1];
+        vec.ct_sort();
+        assert_eq!(vec, [isize::MIN, -1, 0, 1, isize::MAX]);
+    }
+
+    #[test]
+    fn sort_i32_extremes() {
+        let mut vec = vec![i32::MAX, 0, i32::MIN, -1, 1, i32::MIN + 1, i32::MAX - 1];
+        vec.ct_sort();
+        assert_eq!(
+            vec,
+            [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX]
+        );
+    }
+
+    macro_rules! sort_desc_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    // A narrow value range forces plenty of duplicates.
+                    let mut vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort_desc();
+                    expected.ct_sort();
+                    expected.reverse();
+                    assert_eq!(vec, expected);
+                }
+            }
+        };
+    }
+
+    sort_desc_random!(sort_desc_u8_random, u8, 256);
+    sort_desc_random!(sort_desc_u32_random, u32, 256);
+    sort_desc_random!(sort_desc_i64_random, i64, 256);
+    sort_desc_random!(sort_desc_u128_random, u128, 256);
+
+    #[test]
+    fn comparator_count() {
+        let known = [0, 0, 1, 3, 5, 9, 12, 16, 19];
+        for (len, &expected) in known.iter().enumerate() {
+            assert_eq!(network_comparators(len), expected);
+        }
+        for len in 0..600 {
+            let mut counted = 0;
+            crate::network(len, |_, _| counted += 1);
+            assert_eq!(network_comparators(len), counted);
+        }
+    }
+
+    macro_rules! network_array_matches {
+        ($name:ident, $($n:expr),+) => {
+            #[test]
+            fn $name() {
+                $({
+                    const NETWORK: [(usize, usize); network_comparators($n)] =
+                        network_array::<$n, { network_comparators($n) }>();
+                    assert!(NETWORK.iter().copied().eq(network_pairs($n)));
+                })+
+            }
+        };
+    }
+
+    network_array_matches!(network_array_small, 0, 1, 2, 3, 4, 5, 8, 13, 16);
+    network_array_matches!(network_array_large, 33, 100, 255, 256, 257);
+
+    #[test]
+    #[should_panic]
+    fn network_array_wrong_size() {
+        network_array::<8, 18>();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn apply_built_network() {
+        for len in 0..300 {
+            let net = build_network(len);
+            assert_eq!(net.len(), network_comparators(len));
+            let mut vec: Vec<i32> = rand::rng().random_iter().take(len).collect();
+            let mut expected = vec.to_vec();
+            vec.apply_network(&net);
+            expected.ct_sort();
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn small_lengths_match_generic() {
+        for len in 0..=40 {
+            for _ in 0..64 {
+                let mut vec: Vec<i32> = rand::rng()
+                    .random_iter::<i32>()
+                    .take(len)
+                    .map(|x| x % 8)
+                    .collect();
+                let mut expected = vec.to_vec();
+                vec.ct_sort();
+                crate::generic_network_runs(len, |lo, hi, count| {
+                    for k in 0..count {
+                        crate::compare_exchange_at(&mut expected, lo + k, hi + k);
+                    }
+                });
+                assert_eq!(vec, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn power_of_two_runs_match_general() {
+        for len in (0..14).map(|k| 1 << k) {
+            let (mut runs, mut expected) = (Vec::new(), Vec::new());
+            crate::power_of_two_network_runs(len, |lo, hi, count| runs.push((lo, hi, count)));
+            crate::general_network_runs(len, |lo, hi, count| expected.push((lo, hi, count)));
+            assert_eq!(runs, expected);
+        }
+    }
+
+    #[test]
+    fn pairs_match_network() {
+        for len in 0..600 {
+            let mut expected = Vec::new();
+            crate::network(len, |i, j| expected.push((i, j)));
+            assert!(network_pairs(len).eq(expected));
+        }
+    }
+
+    #[test]
+    fn comparators_sort() {
+        for len in 0..200 {
+            let mut vec: Vec<u16> = rand::rng().random_iter().take(len).collect();
+            let mut expected = vec.to_vec();
+            for Comparator { lo, hi } in comparators(len) {
+                assert!(lo < hi);
+                if vec[lo] > vec[hi] {
+                    vec.swap(lo, hi);
                 }
             }
+            expected.ct_sort();
+            assert_eq!(vec, expected);
         }
-    };
-}
+    }
 
-generate_constant_time_sort!(u8);
-generate_constant_time_sort!(u16);
-generate_constant_time_sort!(u32);
-generate_constant_time_sort!(u64);
-generate_constant_time_sort!(u128);
+    #[test]
+    fn top_matches_doubling() {
+        for len in 0..=4096 {
+            let doubled = core::iter::successors(Some(1usize), |t| t.checked_mul(2))
+                .take_while(|t| *t < len)
+                .last();
+            assert_eq!(crate::top(len), doubled);
+        }
+        assert_eq!(crate::top(usize::MAX), Some(1 << (usize::BITS - 1)));
+    }
 
-trait MinMax<T> {
-    fn minmax_at(&mut self, i: usize, j: usize);
-    fn minmax(&mut self, a: T, j: usize) -> T;
-}
+    #[test]
+    fn stride_runs_at_the_top_of_usize() {
+        let top = 1usize << (usize::BITS - 1);
+        let mut runs = Vec::new();
+        crate::stride_runs(0, usize::MAX - top, top, |start, count| {
+            runs.push((start, count))
+        });
+        assert_eq!(runs, [(0, usize::MAX - top)]);
+        assert!(crate::stride_run_iter(0, usize::MAX - top, top).eq(runs));
 
-macro_rules! generate_minmax {
-    ($t:ty) => {
-        impl MinMax<$t> for [$t] {
-            #[inline(always)]
-            fn minmax_at(&mut self, i: usize, j: usize) {
-                let a = self[i];
-                let b = self[j];
-                let swap_operator = (a ^ b) & a.gt_mask(b);
-                self[i] = a ^ swap_operator;
-                self[j] = b ^ swap_operator;
-            }
+        let p = top >> 1;
+        let mut runs = Vec::new();
+        crate::stride_runs(p - 2, usize::MAX - p, p, |start, count| {
+            runs.push((start, count))
+        });
+        assert_eq!(runs, [(p - 2, 2), (2 * p, p - 1)]);
+        assert!(crate::stride_run_iter(p - 2, usize::MAX - p, p).eq(runs));
+    }
 
-            #[inline(always)]
-            fn minmax(&mut self, a: $t, j: usize) -> $t {
-                let b = self[j];
-                let swap_operator = (a ^ b) & a.gt_mask(b);
-                self[j] = b ^ swap_operator;
-                a ^ swap_operator
+    #[test]
+    fn pairs_for_the_longest_lengths() {
+        let top = 1usize << (usize::BITS - 1);
+        assert!(network_pairs(crate::MAX_LEN)
+            .take(2)
+            .eq([(0, top), (1, top + 1)]));
+        assert!(network_pairs(crate::MAX_LEN)
+            .take(1 << 12)
+            .all(|(i, j)| i < j && j < crate::MAX_LEN));
+        assert!(network_pairs(top + 1).take(2).eq([(0, top), (0, top >> 1)]));
+    }
+
+    #[test]
+    fn sort_adversarial_patterns() {
+        for len in 0..300u32 {
+            let patterns: [Vec<u32>; 6] = [
+                vec![7; len as usize],
+                (0..len).collect(),
+                (0..len).rev().collect(),
+                (0..len).map(|i| i.min(len - i)).collect(),
+                (0..len).map(|i| (i % 17 == 0) as u32).collect(),
+                (0..len).map(|i| i % 3).collect(),
+            ];
+            for mut vec in patterns {
+                let mut expected = vec.to_vec();
+                vec.ct_sort();
+                expected.sort();
+                assert_eq!(vec, expected);
             }
         }
-    };
-}
-
-generate_minmax!(u8);
-generate_minmax!(u16);
-generate_minmax!(u32);
-generate_minmax!(u64);
-generate_minmax!(u128);
+    }
 
-trait GreaterThanMask {
-    fn gt_mask(self, other: Self) -> Self;
-}
+    #[test]
+    fn sort_lengths_near_powers_of_two() {
+        for k in 1..=16 {
+            for len in [(1 << k) - 1, 1 << k, (1 << k) + 1] {
+                let mut vec: Vec<u8> = rand::rng().random_iter().take(len).collect();
+                let mut expected = vec.to_vec();
+                vec.ct_sort();
+                expected.sort();
+                assert_eq!(vec, expected);
+            }
+        }
+    }
 
-macro_rules! generate_gt_mask {
-    ($t:ty, $bits:expr) => {
-        impl GreaterThanMask for $t {
-            #[inline(never)]
-            fn gt_mask(self, other: Self) -> Self {
-                let mut result = other.wrapping_sub(self);
-                result ^= self;
-                result |= other ^ self;
-                result ^= other;
-                result >>= $bits - 1;
-                result.wrapping_neg()
+    #[test]
+    fn pairs_sort_all_zero_one_inputs() {
+        // By the zero-one principle, sorting every binary input proves the network sorts.
+        for len in 0..=14 {
+            let pairs: Vec<(usize, usize)> = network_pairs(len).collect();
+            for input in 0u32..(1 << len) {
+                let mut bits = input;
+                for &(i, j) in &pairs {
+                    let (a, b) = ((bits >> i) & 1, (bits >> j) & 1);
+                    bits ^= ((a & !b) << i) | ((a & !b) << j);
+                }
+                let ones = input.count_ones();
+                assert_eq!(bits, ((1u32 << ones) - 1) << (len as u32 - ones));
             }
         }
-    };
-}
+    }
 
-generate_gt_mask!(u8, 8);
-generate_gt_mask!(u16, 16);
-generate_gt_mask!(u32, 32);
-generate_gt_mask!(u64, 64);
-generate_gt_mask!(u128, 128);
+    #[test]
+    fn sort_by_order_matches_wrappers() {
+        let vec: Vec<u32> = rand::rng().random_iter().take(100).collect();
 
-#[cfg(test)]
-mod tests {
-    use rand::Rng;
+        let mut ascending = vec.to_vec();
+        let mut expected = vec.to_vec();
+        ascending.ct_sort_by_order(SortOrder::Ascending);
+        expected.ct_sort();
+        assert_eq!(ascending, expected);
 
-    use crate::ConstantTimeSort;
-    use crate::GreaterThanMask;
+        let mut descending = vec.to_vec();
+        let mut expected = vec.to_vec();
+        descending.ct_sort_by_order(SortOrder::Descending);
+        expected.ct_sort_desc();
+        assert_eq!(descending, expected);
+    }
 
-    macro_rules! sort_random {
+    #[cfg(feature = "alloc")]
+    macro_rules! argsort_random {
         ($name:ident, $t:ty, $n:expr) => {
             #[test]
             fn $name() {
                 for count in 0..$n {
-                    let mut vec: Vec<$t> = rand::thread_rng()
-                        .sample_iter(rand::distributions::Standard)
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    let original = vec.to_vec();
+                    let mut expected: Vec<u32> = (0..count as u32).collect();
+                    expected.sort_by_key(|&i| vec[i as usize]);
+                    assert_eq!(vec.ct_argsort(), expected);
+                    assert_eq!(vec, original);
+                }
+            }
+        };
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn argsort_stable_keeps_buckets_in_order() {
+        for count in 0..256 {
+            let vec: Vec<LowByte> = rand::rng()
+                .random_iter::<u32>()
+                .take(count)
+                .map(|x| LowByte(x & 0xFFFF_0003))
+                .collect();
+            let mut expected: Vec<u32> = (0..count as u32).collect();
+            expected.sort_by_key(|&i| vec[i as usize].0 as u8);
+            assert_eq!(vec.ct_argsort_stable(), expected);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    argsort_random!(argsort_u8_random, u8, 256);
+    #[cfg(feature = "alloc")]
+    argsort_random!(argsort_u32_random, u32, 256);
+    #[cfg(feature = "alloc")]
+    argsort_random!(argsort_i64_random, i64, 256);
+    #[cfg(feature = "alloc")]
+    argsort_random!(argsort_u128_random, u128, 256);
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_with_unique_mask_marks_firsts() {
+        for count in 0..256 {
+            let mut vec: Vec<u8> = rand::rng()
+                .random_iter::<u8>()
+                .take(count)
+                .map(|x| x % 16)
+                .collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            let mask = vec.ct_sort_with_unique_mask();
+            assert_eq!(vec, expected);
+            let firsts: Vec<bool> = (0..count)
+                .map(|i| i == 0 || expected[i] != expected[i - 1])
+                .collect();
+            assert_eq!(mask, firsts);
+        }
+    }
+
+    #[test]
+    fn sort_range_leaves_rest() {
+        for count in 0..64 {
+            for (begin, end) in [
+                (0, count),
+                (0, count / 2),
+                (count / 3, count),
+                (count / 4, count / 2),
+            ] {
+                let mut vec: Vec<u16> = rand::rng().random_iter().take(count).collect();
+                let mut expected = vec.to_vec();
+                vec.ct_sort_range(begin..end);
+                expected[begin..end].sort();
+                assert_eq!(vec, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_range_out_of_bounds() {
+        [3u8, 2, 1].ct_sort_range(1..4);
+    }
+
+    #[test]
+    fn sort_with_reorders_values() {
+        for count in 0..256 {
+            let mut keys: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut values: Vec<u64> = keys.iter().map(|&k| u64::from(k) << 8 | 0xab).collect();
+            keys.ct_sort_with(&mut values);
+            let mut expected = keys.to_vec();
+            expected.sort();
+            assert_eq!(keys, expected);
+            assert!(keys
+                .iter()
+                .zip(&values)
+                .all(|(&k, &v)| v == u64::from(k) << 8 | 0xab));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sort_with_length_mismatch() {
+        [3u32, 1, 2].ct_sort_with(&mut [0u64; 2]);
+    }
+
+    #[test]
+    fn sort_arrays() {
+        let mut empty: [u32; 0] = [];
+        empty.ct_sort();
+        assert_eq!(empty, []);
+
+        let mut single = [42u32];
+        single.ct_sort();
+        assert_eq!(single, [42]);
+
+        let mut array = [0u32; 100];
+        rand::rng().fill(&mut array[..]);
+        let mut expected = array;
+        array.ct_sort();
+        expected.sort();
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_vec_generically() {
+        fn sort_descending<S: ConstantTimeSort + ?Sized>(container: &mut S) {
+            container.ct_sort_by_order(SortOrder::Descending);
+        }
+
+        let mut vec: Vec<i64> = rand::rng().random_iter().take(100).collect();
+        let mut expected = vec.to_vec();
+        sort_descending(&mut vec);
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(vec, expected);
+        vec.ct_sort();
+        expected.reverse();
+        assert_eq!(vec, expected);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn sort_derived_fixed_width_key() {
+        use crate::FixedWidthKey;
+
+        #[derive(Clone, Copy, Debug, PartialEq, FixedWidthKey)]
+        struct Record {
+            id: u16,
+            #[sort_key]
+            pub score: i32,
+            tags: [u8; 3],
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, FixedWidthKey)]
+        struct Pair(u8, #[sort_key] u64);
+
+        let mut records: Vec<Record> = (0..200u16)
+            .map(|id| Record {
+                id,
+                score: rand::rng().random_range(-50..50),
+                tags: [id as u8; 3],
+            })
+            .collect();
+        let mut expected = records.to_vec();
+        records.ct_sort_stable();
+        expected.sort_by_key(|record| record.score);
+        assert_eq!(records, expected);
+        assert!(records.iter().all(|record| record.key() == record.score));
+
+        let mut pairs = [Pair(1, 30), Pair(2, 10), Pair(3, 20)];
+        pairs.ct_sort();
+        assert_eq!(pairs, [Pair(2, 10), Pair(3, 20), Pair(1, 30)]);
+    }
+
+    #[test]
+    fn sort_bools() {
+        let mut runs = [
+            true, true, false, true, false, false, false, true, true, true, false,
+        ];
+        runs.ct_sort();
+        assert_eq!(
+            runs,
+            [false, false, false, false, false, true, true, true, true, true, true]
+        );
+        runs.ct_sort_desc();
+        assert_eq!(
+            runs,
+            [true, true, true, true, true, true, false, false, false, false, false]
+        );
+
+        for count in 0..256 {
+            let mut vec: Vec<bool> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort();
+            expected.sort();
+            assert_eq!(vec, expected);
+        }
+    }
+
+    macro_rules! sort_float_total_order {
+        ($name:ident, $f:ident, $u:ty) => {
+            #[test]
+            fn $name() {
+                let mut specials = [
+                    $f::NAN,
+                    -$f::NAN,
+                    $f::INFINITY,
+                    $f::NEG_INFINITY,
+                    0.0,
+                    -0.0,
+                    $f::MIN_POSITIVE,
+                    -$f::MIN_POSITIVE,
+                    $f::from_bits(1),
+                    $f::MAX,
+                    $f::MIN,
+                    1.5,
+                    -1.5,
+                ];
+                let mut expected = specials;
+                specials.ct_sort();
+                expected.sort_by($f::total_cmp);
+                assert!(specials
+                    .iter()
+                    .map(|x| x.to_bits())
+                    .eq(expected.iter().map(|x| x.to_bits())));
+
+                for count in 0..256 {
+                    let mut vec: Vec<$f> = rand::rng()
+                        .random_iter::<$u>()
+                        .map($f::from_bits)
+                        .filter(|x| x.is_finite())
                         .take(count)
                         .collect();
                     let mut expected = vec.to_vec();
                     vec.ct_sort();
+                    expected.sort_by($f::total_cmp);
+                    assert!(vec
+                        .iter()
+                        .map(|x| x.to_bits())
+                        .eq(expected.iter().map(|x| x.to_bits())));
+                }
+            }
+        };
+    }
+
+    sort_float_total_order!(sort_f32_total_order, f32, u32);
+    sort_float_total_order!(sort_f64_total_order, f64, u64);
+
+    #[test]
+    fn sort_nonzero() {
+        use core::num::{NonZeroI64, NonZeroU32};
+
+        for count in 0..256 {
+            let mut vec: Vec<NonZeroU32> = rand::rng()
+                .random_iter()
+                .filter_map(NonZeroU32::new)
+                .take(count)
+                .collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort();
+            expected.sort();
+            assert_eq!(vec, expected);
+
+            let mut vec: Vec<NonZeroI64> = rand::rng()
+                .random_iter()
+                .filter_map(NonZeroI64::new)
+                .take(count)
+                .collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort_desc();
+            expected.sort_by(|a, b| b.cmp(a));
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn sort_wrapping() {
+        use core::num::Wrapping;
+
+        for count in 0..256 {
+            let mut vec: Vec<Wrapping<i16>> = rand::rng()
+                .random_iter()
+                .take(count)
+                .map(Wrapping)
+                .collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort();
+            expected.sort();
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn sort_reverse() {
+        use core::cmp::Reverse;
+
+        for count in 0..256 {
+            let values: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut vec: Vec<Reverse<u32>> = values.iter().copied().map(Reverse).collect();
+            let mut expected = values.to_vec();
+            vec.ct_sort();
+            expected.ct_sort_desc();
+            assert!(vec.iter().map(|r| r.0).eq(expected));
+        }
+    }
+
+    #[test]
+    fn sort_chars() {
+        let mut alphabet: Vec<char> = "zürich🦀Ωa\u{10FFFF}\u{0}\u{D7FF}\u{E000}"
+            .chars()
+            .collect();
+        let mut expected = alphabet.to_vec();
+        alphabet.ct_sort();
+        expected.sort();
+        assert_eq!(alphabet, expected);
+
+        for count in 0..256 {
+            let mut vec: Vec<char> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort_desc();
+            expected.sort_by(|a, b| b.cmp(a));
+            assert_eq!(vec, expected);
+        }
+    }
+
+    macro_rules! sort_byte_keys_random {
+        ($name:ident, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..128 {
+                    let mut vec = vec![[0u8; $n]; count];
+                    for key in vec.iter_mut() {
+                        // A narrow alphabet makes shared prefixes common.
+                        rand::rng().fill(&mut key[..]);
+                        key.iter_mut().for_each(|b| *b %= 3);
+                    }
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort();
                     expected.sort();
                     assert_eq!(vec, expected);
                 }
@@ -131,11 +2147,233 @@ mod tests {
         };
     }
 
-    sort_random!(sort_u8_random, u8, 1024);
-    sort_random!(sort_u16_random, u16, 1024);
-    sort_random!(sort_u32_random, u32, 1024);
-    sort_random!(sort_u64_random, u64, 1024);
-    sort_random!(sort_u128_random, u128, 1024);
+    sort_byte_keys_random!(sort_byte_keys_1_random, 1);
+    sort_byte_keys_random!(sort_byte_keys_16_random, 16);
+    sort_byte_keys_random!(sort_byte_keys_32_random, 32);
+    sort_byte_keys_random!(sort_byte_keys_33_random, 33);
+
+    #[test]
+    fn sort_hashes_random() {
+        for count in 0..128 {
+            let mut hashes: Vec<[u8; 32]> = rand::rng().random_iter().take(count).collect();
+            // Duplicates and hashes sharing all but their last byte.
+            if count >= 4 {
+                hashes[1] = hashes[0];
+                hashes[3] = hashes[2];
+                hashes[3][31] ^= 1;
+            }
+            let mut expected = hashes.to_vec();
+            hashes.ct_sort();
+            expected.sort();
+            assert_eq!(hashes, expected);
+        }
+    }
+
+    #[test]
+    fn sort_pairs_lexicographic() {
+        for count in 0..256 {
+            let mut vec: Vec<(u32, u32)> = rand::rng()
+                .random_iter::<(u32, u32)>()
+                .take(count)
+                .map(|(primary, secondary)| (primary % 8, secondary))
+                .collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort();
+            expected.sort();
+            assert_eq!(vec, expected);
+        }
+    }
+
+    /// Orders by the low byte only, so that sorting it differs from sorting the raw integers.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub(crate) struct LowByte(pub(crate) u32);
+
+    impl ConditionalSwap for LowByte {
+        fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+            u32::conditional_swap(&mut a.0, &mut b.0, mask);
+        }
+    }
+
+    impl CtOrd for LowByte {
+        fn ct_gt_mask(self, other: Self) -> Mask {
+            (self.0 as u8).ct_gt_mask(other.0 as u8)
+        }
+    }
+
+    #[test]
+    fn sort_user_ct_ord() {
+        for count in 0..256 {
+            let mut vec: Vec<LowByte> =
+                rand::rng().random_iter().take(count).map(LowByte).collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort();
+            expected.sort_by_key(|x| x.0 as u8);
+            assert!(vec
+                .iter()
+                .map(|x| x.0 as u8)
+                .eq(expected.iter().map(|x| x.0 as u8)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_stable_keeps_ties_in_order() {
+        for count in 0..256 {
+            let mut vec: Vec<LowByte> = rand::rng()
+                .random_iter::<u32>()
+                .take(count)
+                .map(|x| LowByte(x & 0xFFFF_0007))
+                .collect();
+            let mut expected = vec.to_vec();
+            vec.ct_sort_stable();
+            expected.sort_by_key(|x| x.0 as u8);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", target_pointer_width = "64"))]
+    fn try_sort_stable_rejects_long_slices() {
+        // A zero-sized element makes a slice longer than `u32::MAX` free to build.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Unit;
+
+        impl ConditionalSwap for Unit {
+            fn conditional_swap(_: &mut Self, _: &mut Self, _: Mask) {}
+        }
+
+        impl CtOrd for Unit {
+            fn ct_gt_mask(self, _: Self) -> Mask {
+                Mask::FALSE
+            }
+        }
+
+        // SAFETY: a dangling, aligned pointer is valid for any number of zero-sized elements.
+        let long = unsafe {
+            core::slice::from_raw_parts_mut(
+                core::ptr::NonNull::<Unit>::dangling().as_ptr(),
+                u32::MAX as usize + 1,
+            )
+        };
+        assert_eq!(
+            long.try_ct_sort_stable(),
+            Err(crate::SortError::TooLong {
+                max: u32::MAX as usize
+            })
+        );
+        let mut short = [3u8, 1, 2];
+        assert_eq!(short.try_ct_sort_stable(), Ok(()));
+        assert_eq!(short, [1, 2, 3]);
+    }
+
+    #[test]
+    fn mask_ops() {
+        assert_eq!(Mask::from_bool(true), Mask::TRUE);
+        assert_eq!(Mask::from_bool(false), Mask::FALSE);
+        assert_eq!(Mask::TRUE & Mask::FALSE, Mask::FALSE);
+        assert_eq!(Mask::TRUE | Mask::FALSE, Mask::TRUE);
+        assert_eq!(!Mask::FALSE, Mask::TRUE);
+        assert_eq!(Mask::from_bit(1), Mask::TRUE);
+        assert_eq!(Mask::from_bit(0), Mask::FALSE);
+        assert_eq!(Mask::TRUE.bit(), 1);
+        assert_eq!(Mask::FALSE.bit(), 0);
+        assert!(bool::from(5u64.ct_gt_mask(3)));
+        assert!(bool::from((-1i16).ct_eq_mask(-1)));
+        assert!(!bool::from(i128::MIN.ct_gt_mask(0)));
+    }
+
+    #[test]
+    fn mask_from_bit_is_canonical() {
+        use core::num::NonZeroU8;
+
+        for bit in 0..=u8::MAX {
+            let mask = Mask::from_bit(bit);
+            assert_eq!(
+                mask,
+                if bit & 1 == 1 {
+                    Mask::TRUE
+                } else {
+                    Mask::FALSE
+                }
+            );
+        }
+        assert_eq!(Mask::from_bit(2), Mask::FALSE);
+        assert_eq!(Mask::from_bit(0xFF), Mask::TRUE);
+
+        let (one, two) = (NonZeroU8::new(1).unwrap(), NonZeroU8::new(2).unwrap());
+        let (mut a, mut b) = (one, two);
+        NonZeroU8::conditional_swap(&mut a, &mut b, Mask::from_bit(2));
+        assert_eq!((a, b), (one, two));
+        NonZeroU8::conditional_swap(&mut a, &mut b, Mask::from_bit(3));
+        assert_eq!((a, b), (two, one));
+    }
+
+    #[test]
+    fn conditional_reverse_random() {
+        for count in 0..64 {
+            let original: Vec<(u8, i64)> = rand::rng().random_iter().take(count).collect();
+            let mut vec = original.to_vec();
+            ct_conditional_reverse(&mut vec, false);
+            assert_eq!(vec, original);
+            ct_conditional_reverse(&mut vec, true);
+            let mut expected = original.to_vec();
+            expected.reverse();
+            assert_eq!(vec, expected);
+        }
+    }
+
+    macro_rules! cswap_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for _ in 0..($n) {
+                    let a: $t = rand::rng().random();
+                    let b: $t = rand::rng().random();
+                    let (mut x, mut y) = (a, b);
+                    ct_cswap(&mut x, &mut y, 0);
+                    assert_eq!((x, y), (a, b));
+                    ct_cswap(&mut x, &mut y, !0);
+                    assert_eq!((x, y), (b, a));
+                }
+            }
+        };
+    }
+
+    cswap_random!(cswap_u8_random, u8, 1024);
+    cswap_random!(cswap_u16_random, u16, 1024);
+    cswap_random!(cswap_u32_random, u32, 1024);
+    cswap_random!(cswap_u64_random, u64, 1024);
+    cswap_random!(cswap_u128_random, u128, 1024);
+    cswap_random!(cswap_i8_random, i8, 1024);
+    cswap_random!(cswap_i16_random, i16, 1024);
+    cswap_random!(cswap_i32_random, i32, 1024);
+    cswap_random!(cswap_i64_random, i64, 1024);
+    cswap_random!(cswap_i128_random, i128, 1024);
+
+    macro_rules! select_mask_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for _ in 0..($n) {
+                    let a: $t = rand::rng().random();
+                    let b: $t = rand::rng().random();
+                    assert_eq!(select_mask(!0, a, b), a);
+                    assert_eq!(select_mask(0, a, b), b);
+                }
+            }
+        };
+    }
+
+    select_mask_random!(select_mask_u8_random, u8, 1024);
+    select_mask_random!(select_mask_u16_random, u16, 1024);
+    select_mask_random!(select_mask_u32_random, u32, 1024);
+    select_mask_random!(select_mask_u64_random, u64, 1024);
+    select_mask_random!(select_mask_u128_random, u128, 1024);
+    select_mask_random!(select_mask_i8_random, i8, 1024);
+    select_mask_random!(select_mask_i16_random, i16, 1024);
+    select_mask_random!(select_mask_i32_random, i32, 1024);
+    select_mask_random!(select_mask_i64_random, i64, 1024);
+    select_mask_random!(select_mask_i128_random, i128, 1024);
 
     #[test]
     fn gt_mask_u8_exhaustive() {
@@ -146,14 +2384,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn gt_mask_i8_exhaustive() {
+        for lhs in i8::MIN..=i8::MAX {
+            for rhs in i8::MIN..=i8::MAX {
+                assert_eq!(lhs.gt_mask(rhs), if lhs > rhs { -1 } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn lt_mask_u8_exhaustive() {
+        for lhs in 0..=u8::MAX {
+            for rhs in 0..=u8::MAX {
+                assert_eq!(lhs.lt_mask(rhs), if lhs < rhs { u8::MAX } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn lt_mask_i8_exhaustive() {
+        for lhs in i8::MIN..=i8::MAX {
+            for rhs in i8::MIN..=i8::MAX {
+                assert_eq!(lhs.lt_mask(rhs), if lhs < rhs { -1 } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn eq_mask_u8_exhaustive() {
+        for lhs in 0..=u8::MAX {
+            for rhs in 0..=u8::MAX {
+                assert_eq!(lhs.eq_mask(rhs), if lhs == rhs { u8::MAX } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn gt_mask_i32_extremes() {
+        let values = [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX];
+        for &lhs in &values {
+            for &rhs in &values {
+                assert_eq!(lhs.gt_mask(rhs), if lhs > rhs { -1 } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn gt_mask_slice_random() {
+        for len in 0..100 {
+            let a: Vec<u32> = rand::rng().random_iter().take(len).collect();
+            let mut b: Vec<u32> = rand::rng().random_iter().take(len).collect();
+            b[..len / 2].copy_from_slice(&a[..len / 2]);
+            let mut out = vec![1; len];
+            gt_mask_slice(&a, &b, &mut out);
+            for k in 0..len {
+                assert_eq!(out[k], a[k].gt_mask(b[k]));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn gt_mask_slice_length_mismatch() {
+        gt_mask_slice(&[1, 2], &[3, 4], &mut [0; 3]);
+    }
+
     macro_rules! gt_mask_random {
         ($name:ident, $t:ty, $n:expr) => {
+            gt_mask_random!($name, $t, $t, $n);
+        };
+        ($name:ident, $t:ty, $sample:ty, $n:expr) => {
             #[test]
             fn $name() {
                 for _ in 0..($n) {
-                    let lhs: $t = rand::thread_rng().gen();
-                    let rhs: $t = rand::thread_rng().gen();
-                    assert_eq!(lhs.gt_mask(rhs), if lhs > rhs { <$t>::MAX } else { 0 });
+                    let lhs = rand::rng().random::<$sample>() as $t;
+                    let rhs = rand::rng().random::<$sample>() as $t;
+                    assert_eq!(lhs.gt_mask(rhs), if lhs > rhs { !0 } else { 0 });
                 }
             }
         };
@@ -163,4 +2470,38 @@ mod tests {
     gt_mask_random!(gt_mask_u32_random, u32, 1024);
     gt_mask_random!(gt_mask_u64_random, u64, 1024);
     gt_mask_random!(gt_mask_u128_random, u128, 1024);
+    gt_mask_random!(gt_mask_i16_random, i16, 1024);
+    gt_mask_random!(gt_mask_i32_random, i32, 1024);
+    gt_mask_random!(gt_mask_i64_random, i64, 1024);
+    gt_mask_random!(gt_mask_i128_random, i128, 1024);
+    gt_mask_random!(gt_mask_usize_random, usize, PointerWidth, 1024);
+    gt_mask_random!(gt_mask_isize_random, isize, PointerWidth, 1024);
+
+    macro_rules! lt_mask_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            lt_mask_random!($name, $t, $t, $n);
+        };
+        ($name:ident, $t:ty, $sample:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for _ in 0..($n) {
+                    let lhs = rand::rng().random::<$sample>() as $t;
+                    let rhs = rand::rng().random::<$sample>() as $t;
+                    assert_eq!(lhs.lt_mask(rhs), if lhs < rhs { !0 } else { 0 });
+                    assert_eq!(lhs.lt_mask(lhs), 0);
+                }
+            }
+        };
+    }
+
+    lt_mask_random!(lt_mask_u16_random, u16, 1024);
+    lt_mask_random!(lt_mask_u32_random, u32, 1024);
+    lt_mask_random!(lt_mask_u64_random, u64, 1024);
+    lt_mask_random!(lt_mask_u128_random, u128, 1024);
+    lt_mask_random!(lt_mask_i16_random, i16, 1024);
+    lt_mask_random!(lt_mask_i32_random, i32, 1024);
+    lt_mask_random!(lt_mask_i64_random, i64, 1024);
+    lt_mask_random!(lt_mask_i128_random, i128, 1024);
+    lt_mask_random!(lt_mask_usize_random, usize, PointerWidth, 1024);
+    lt_mask_random!(lt_mask_isize_random, isize, PointerWidth, 1024);
 }
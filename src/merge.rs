@@ -0,0 +1,339 @@
+use crate::{compare_exchange_at, CtOrd};
+
+#[cfg(feature = "alloc")]
+use crate::scratch::Scratch;
+
+/// Merges the sorted slices `a` and `b` into `out` with a bitonic merge network, so that the
+/// memory access pattern depends only on the lengths of the inputs. Its comparators come in runs
+/// of consecutive pairs at a fixed stride, which use the same SIMD kernels as
+/// [`ct_sort`](crate::ConstantTimeSort::ct_sort) for `u32` and the other types that have them.
+///
+/// Panics if `out.len() != a.len() + b.len()`.
+pub fn ct_merge<T: CtOrd>(a: &[T], b: &[T], out: &mut [T]) {
+    assert_eq!(
+        out.len(),
+        a.len() + b.len(),
+        "output length must equal the combined input length"
+    );
+    bitonic_merge_input(a, b, out);
+    // Each run goes to the vectorized kernel for the element type where there is one.
+    bitonic_merge_runs(0, out.len(), &mut |lo, hi, count| {
+        T::compare_exchange_run(out, lo, hi, count)
+    });
+}
+
+/// Writes `a` reversed followed by `b` to `out`, the bitonic sequence that
+/// [`bitonic_merge_network`] sorts.
+pub(crate) fn bitonic_merge_input<T: Copy>(a: &[T], b: &[T], out: &mut [T]) {
+    let (head, tail) = out.split_at_mut(a.len());
+    for (dst, &src) in head.iter_mut().zip(a.iter().rev()) {
+        *dst = src;
+    }
+    tail.copy_from_slice(b);
+}
+
+/// Merges the sorted runs `slice[..mid]` and `slice[mid..]` in place with Batcher's odd-even
+/// merge network, without any auxiliary buffer. The memory access pattern depends only on the
+/// length and `mid`, and the result is what [`ct_merge`] produces from the two runs.
+///
+/// Panics if `mid > slice.len()`.
+pub fn ct_merge_in_place<T: CtOrd>(slice: &mut [T], mid: usize) {
+    assert!(mid <= slice.len(), "mid out of bounds");
+    odd_even_merge_network(mid, slice.len() - mid, |i, j| {
+        compare_exchange_at(slice, i, j)
+    });
+}
+
+/// Merges the sorted slices `a` and `b` into `out` with Batcher's odd-even merge network, leaving
+/// both inputs untouched. The merge is stable: of two equal elements, the one from `a` comes
+/// first, and among elements from the same input the original order is kept. The memory access
+/// pattern depends only on the lengths of the inputs.
+///
+/// Panics if `out.len() != a.len() + b.len()`.
+#[cfg(feature = "alloc")]
+pub fn ct_merge_into<T: CtOrd>(a: &[T], b: &[T], out: &mut [T]) {
+    check_merge_into_lengths(a, b, out);
+    let mut pairs: Scratch<(T, u32)> = a.iter().chain(b).copied().zip(0..).collect();
+    odd_even_merge_pairs(a.len(), b.len(), &mut pairs, out);
+}
+
+/// Like [`ct_merge_into`], but uses the first `a.len() + b.len()` elements of `scratch` instead
+/// of allocating, so that it needs no allocator. The scratch contents on entry do not matter;
+/// on return they hold copies of the merged elements, which the caller may want to wipe.
+///
+/// Panics if `out.len() != a.len() + b.len()` or if `scratch` is shorter than that.
+pub fn ct_merge_into_in<T: CtOrd>(a: &[T], b: &[T], out: &mut [T], scratch: &mut [(T, u32)]) {
+    check_merge_into_lengths(a, b, out);
+    assert!(scratch.len() >= out.len(), "scratch buffer too short");
+    let pairs = &mut scratch[..out.len()];
+    for (pair, (&x, index)) in pairs.iter_mut().zip(a.iter().chain(b).zip(0..)) {
+        *pair = (x, index);
+    }
+    odd_even_merge_pairs(a.len(), b.len(), pairs, out);
+}
+
+fn check_merge_into_lengths<T>(a: &[T], b: &[T], out: &[T]) {
+    assert_eq!(
+        out.len(),
+        a.len() + b.len(),
+        "output length must equal the combined input length"
+    );
+    assert!(
+        out.len() <= u32::MAX as usize,
+        "slices too long for u32 indices"
+    );
+}
+
+/// Merges the two runs of `(element, index)` pairs and copies the elements to `out`. The
+/// indices break ties, which makes the merge stable.
+fn odd_even_merge_pairs<T: CtOrd>(
+    a_len: usize,
+    b_len: usize,
+    pairs: &mut [(T, u32)],
+    out: &mut [T],
+) {
+    odd_even_merge_network(a_len, b_len, |i, j| compare_exchange_at(pairs, i, j));
+    for (dst, &(src, _)) in out.iter_mut().zip(pairs.iter()) {
+        *dst = src;
+    }
+}
+
+/// Calls `compare_exchange(i, j)`, with `i < j`, for every comparator of Batcher's odd-even
+/// merge network that sorts a run of `a_len` elements followed by a run of `b_len` elements.
+///
+/// The runs are thought of as padded to a common power of two, the first with minimal values in
+/// front and the second with maximal values behind. Padding never moves, so comparators that
+/// touch it are skipped and the rest are shifted back onto the real elements.
+fn odd_even_merge_network<F: FnMut(usize, usize)>(
+    a_len: usize,
+    b_len: usize,
+    mut compare_exchange: F,
+) {
+    if a_len == 0 || b_len == 0 {
+        return;
+    }
+    let half = a_len.max(b_len).next_power_of_two();
+    let offset = half - a_len;
+    let end = offset + a_len + b_len;
+    odd_even_merge_step(0, 2 * half - 1, 1, &mut |i, j| {
+        if i >= offset && j < end {
+            compare_exchange(i - offset, j - offset);
+        }
+    });
+}
+
+/// Merges the elements `lo, lo + r, lo + 2r, ..` up to and including `hi`, whose two halves are
+/// sorted, by merging the even and the odd subsequences and then fixing up neighbours.
+fn odd_even_merge_step<F: FnMut(usize, usize)>(
+    lo: usize,
+    hi: usize,
+    r: usize,
+    compare_exchange: &mut F,
+) {
+    let step = 2 * r;
+    if step < hi - lo {
+        odd_even_merge_step(lo, hi, step, compare_exchange);
+        odd_even_merge_step(lo + r, hi, step, compare_exchange);
+        for i in (lo + r..hi - r).step_by(step) {
+            compare_exchange(i, i + r);
+        }
+    } else {
+        compare_exchange(lo, lo + r);
+    }
+}
+
+/// Calls `compare_exchange(i, j)`, with `i < j`, for every comparator of a network that sorts
+/// `lo..lo + len` whenever it holds a descending run followed by an ascending one. The split
+/// between the two runs may fall anywhere.
+pub(crate) fn bitonic_merge_network<F: FnMut(usize, usize)>(
+    lo: usize,
+    len: usize,
+    compare_exchange: &mut F,
+) {
+    bitonic_merge_runs(lo, len, &mut |lo, hi, count| {
+        for k in 0..count {
+            compare_exchange(lo + k, hi + k);
+        }
+    });
+}
+
+/// Calls `run(lo, hi, count)` for the comparators of [`bitonic_merge_network`], grouped into runs
+/// of `count` comparators `(lo + k, hi + k)` that touch disjoint elements, as in
+/// [`network_runs`](crate::network_runs). Each level of the recursion contributes one run.
+fn bitonic_merge_runs<F: FnMut(usize, usize, usize)>(lo: usize, len: usize, run: &mut F) {
+    if len > 1 {
+        let m = 1 << (usize::BITS - 1 - (len - 1).leading_zeros());
+        run(lo, lo + m, len - m);
+        bitonic_merge_runs(lo, m, run);
+        bitonic_merge_runs(lo + m, len - m, run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::{ct_merge, ct_merge_in_place, ct_merge_into_in};
+    #[cfg(feature = "alloc")]
+    use crate::{ct_merge_into, tests::LowByte};
+
+    macro_rules! merge_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for a_len in 0..$n {
+                    for b_len in 0..$n {
+                        let mut a: Vec<$t> = rand::rng().random_iter().take(a_len).collect();
+                        let mut b: Vec<$t> = rand::rng().random_iter().take(b_len).collect();
+                        a.sort();
+                        b.sort();
+                        let mut out = vec![0; a_len + b_len];
+                        ct_merge(&a, &b, &mut out);
+                        let mut expected = [a, b].concat();
+                        expected.sort();
+                        assert_eq!(out, expected);
+                    }
+                }
+            }
+        };
+    }
+
+    merge_random!(merge_u8_random, u8, 48);
+    merge_random!(merge_u32_random, u32, 48);
+    merge_random!(merge_i64_random, i64, 48);
+    merge_random!(merge_u128_random, u128, 48);
+
+    #[test]
+    #[should_panic]
+    fn merge_length_mismatch() {
+        ct_merge(&[1u32, 2], &[3], &mut [0; 4]);
+    }
+
+    #[test]
+    fn merge_u32_runs_match_scalar() {
+        for (a_len, b_len) in [(0, 9), (7, 1), (16, 16), (33, 95), (128, 127), (200, 56)] {
+            let mut a: Vec<u32> = rand::rng()
+                .random_iter()
+                .take(a_len)
+                .map(|x: u32| x % 4)
+                .collect();
+            let mut b: Vec<u32> = rand::rng()
+                .random_iter()
+                .take(b_len)
+                .map(|x: u32| x % 4)
+                .collect();
+            a.sort();
+            b.sort();
+            let mut out = vec![0; a_len + b_len];
+            ct_merge(&a, &b, &mut out);
+            let mut expected = vec![0; a_len + b_len];
+            crate::merge::bitonic_merge_input(&a, &b, &mut expected);
+            crate::merge::bitonic_merge_network(0, expected.len(), &mut |i, j| {
+                crate::compare_exchange_at(&mut expected, i, j)
+            });
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn merge_in_place_random() {
+        for len in 0..64 {
+            for mid in 0..=len {
+                let mut vec: Vec<i16> = rand::rng().random_iter().take(len).collect();
+                vec[..mid].sort();
+                vec[mid..].sort();
+                let mut expected = vec![0; len];
+                ct_merge(&vec[..mid], &vec[mid..], &mut expected);
+                ct_merge_in_place(&mut vec, mid);
+                assert_eq!(vec, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_in_place_mid_out_of_bounds() {
+        ct_merge_in_place(&mut [1u32, 2], 3);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn merge_into_random() {
+        for a_len in 0..48 {
+            for b_len in 0..48 {
+                let mut a: Vec<u32> = rand::rng().random_iter().take(a_len).collect();
+                let mut b: Vec<u32> = rand::rng().random_iter().take(b_len).collect();
+                a.sort();
+                b.sort();
+                let (a_copy, b_copy) = (a.to_vec(), b.to_vec());
+                let mut out = vec![0; a_len + b_len];
+                ct_merge_into(&a, &b, &mut out);
+                assert_eq!((a.to_vec(), b.to_vec()), (a_copy, b_copy));
+                let mut expected = [a, b].concat();
+                expected.sort();
+                assert_eq!(out, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn merge_into_is_stable() {
+        for a_len in 0..48 {
+            for b_len in 0..48 {
+                let sorted_ties = |len| {
+                    let mut vec: Vec<LowByte> = rand::rng()
+                        .random_iter::<u32>()
+                        .take(len)
+                        .map(|x| LowByte(x & 0xFFFF_0003))
+                        .collect();
+                    vec.sort_by_key(|x| x.0 as u8);
+                    vec
+                };
+                let (a, b) = (sorted_ties(a_len), sorted_ties(b_len));
+                let mut out = vec![LowByte(0); a_len + b_len];
+                ct_merge_into(&a, &b, &mut out);
+                let mut expected = [a, b].concat();
+                expected.sort_by_key(|x| x.0 as u8);
+                assert_eq!(out, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn merge_into_in_reuses_scratch() {
+        let mut scratch = vec![(LowByte(0), 0); 96];
+        for a_len in 0..48 {
+            for b_len in 0..48 {
+                let sorted_ties = |len| {
+                    let mut vec: Vec<LowByte> =
+                        rand::rng().random_iter().take(len).map(LowByte).collect();
+                    vec.sort_by_key(|x| x.0 as u8);
+                    vec
+                };
+                let (a, b) = (sorted_ties(a_len), sorted_ties(b_len));
+                let mut out = vec![LowByte(0); a_len + b_len];
+                let mut expected = vec![LowByte(0); a_len + b_len];
+                ct_merge_into_in(&a, &b, &mut out, &mut scratch);
+                ct_merge_into(&a, &b, &mut expected);
+                assert_eq!(out, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_into_in_scratch_too_short() {
+        ct_merge_into_in(&[1u32, 2], &[3], &mut [0; 3], &mut [(0, 0); 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "alloc")]
+    fn merge_into_length_mismatch() {
+        ct_merge_into(&[1u32, 2], &[3], &mut [0; 2]);
+    }
+}
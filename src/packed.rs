@@ -0,0 +1,93 @@
+use crate::scratch::Scratch;
+use crate::ConstantTimeSort;
+
+/// Stable sorting of narrow integers by packing each element and its index into one wider word.
+pub trait ConstantTimeSortStablePacked {
+    /// Sorts ascending, keeping equal elements in their original order, like
+    /// [`ct_sort_stable`](ConstantTimeSort::ct_sort_stable). Each element is packed into the
+    /// high bits of a wider unsigned word and its original index into the low bits, the words
+    /// are sorted, and the elements are unpacked again. This sorts single words instead of
+    /// `(value, index)` pairs.
+    ///
+    /// The index bits bound the length this applies to: 2<sup>24</sup> elements for 8-bit
+    /// types and 2<sup>16</sup> for 16-bit ones, packed into a `u32`, and 2<sup>32</sup> for
+    /// 32-bit ones, packed into a `u64`. Longer slices fall back to `ct_sort_stable`; the choice
+    /// depends only on the length.
+    fn ct_sort_stable_packed(&mut self);
+}
+
+macro_rules! impl_sort_stable_packed {
+    ($($t:ty => $u:ty, $wide:ty, $bias:expr;)+) => {
+        $(
+            impl ConstantTimeSortStablePacked for [$t] {
+                fn ct_sort_stable_packed(&mut self) {
+                    const INDEX_BITS: u32 = <$wide>::BITS - <$t>::BITS;
+                    if self.len() as u64 > 1 << INDEX_BITS {
+                        return self.ct_sort_stable();
+                    }
+                    // The bias flips the sign bit of signed types, so that the unsigned order
+                    // of the packed words matches theirs.
+                    let mut packed: Scratch<$wide> = self
+                        .iter()
+                        .zip(0..)
+                        .map(|(&x, index): (&$t, $wide)| {
+                            (((x as $u) ^ $bias) as $wide) << INDEX_BITS | index
+                        })
+                        .collect();
+                    packed.ct_sort();
+                    for (dst, &word) in self.iter_mut().zip(packed.iter()) {
+                        *dst = (((word >> INDEX_BITS) as $u) ^ $bias) as $t;
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_sort_stable_packed! {
+    u8 => u8, u32, 0;
+    i8 => u8, u32, 1 << 7;
+    u16 => u16, u32, 0;
+    i16 => u16, u32, 1 << 15;
+    u32 => u32, u64, 0;
+    i32 => u32, u64, 1 << 31;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ConstantTimeSortStablePacked;
+
+    macro_rules! sort_stable_packed_random {
+        ($name:ident, $t:ty) => {
+            #[test]
+            fn $name() {
+                for count in 0..256 {
+                    let mut vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    let mut expected = vec.to_vec();
+                    vec.ct_sort_stable_packed();
+                    expected.sort();
+                    assert_eq!(vec, expected);
+                }
+            }
+        };
+    }
+
+    sort_stable_packed_random!(sort_stable_packed_u8_random, u8);
+    sort_stable_packed_random!(sort_stable_packed_i8_random, i8);
+    sort_stable_packed_random!(sort_stable_packed_u16_random, u16);
+    sort_stable_packed_random!(sort_stable_packed_i16_random, i16);
+    sort_stable_packed_random!(sort_stable_packed_u32_random, u32);
+    sort_stable_packed_random!(sort_stable_packed_i32_random, i32);
+
+    #[test]
+    fn sort_stable_packed_fallback() {
+        let mut vec: Vec<i16> = rand::rng().random_iter().take((1 << 16) + 1).collect();
+        let mut expected = vec.to_vec();
+        vec.ct_sort_stable_packed();
+        expected.sort();
+        assert_eq!(vec, expected);
+    }
+}
@@ -0,0 +1,358 @@
+use core::num::NonZeroUsize;
+use std::sync::Barrier;
+use std::thread;
+
+use std::vec::Vec;
+
+use crate::merge::bitonic_merge_input;
+use crate::permutation::is_permutation;
+use crate::scratch::Scratch;
+use crate::{apply_permutation, ct_merge, network_layers, stride_runs, ConstantTimeSort, CtOrd};
+
+/// Multi-threaded sorting for large slices.
+pub trait ConstantTimeParallelSort {
+    /// Sorts ascending with the same network as [`ct_sort`](ConstantTimeSort::ct_sort),
+    /// spreading the comparators of each layer over one scoped thread per available core.
+    /// Layers still run one after another; within a layer every thread takes a fixed range of
+    /// indices, so the division of work depends only on the length. The result is identical to
+    /// `ct_sort`. Short slices are sorted on the calling thread.
+    fn ct_par_sort(&mut self);
+}
+
+/// Below this length spawning threads costs more than it saves.
+const MIN_PARALLEL_LEN: usize = 1 << 14;
+
+/// Merges the sorted slices `a` and `b` into `out` with the same network as [`ct_merge`],
+/// spreading the comparators of each layer over one scoped thread per available core. As in
+/// [`ct_par_sort`](ConstantTimeParallelSort::ct_par_sort), layers run one after another and
+/// every thread takes a fixed range of each, so the division of work depends only on the
+/// lengths. The result is identical to `ct_merge`. Short inputs are merged on the calling thread.
+///
+/// Panics if `out.len() != a.len() + b.len()`.
+pub fn ct_par_merge<T: CtOrd + Send>(a: &[T], b: &[T], out: &mut [T]) {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if threads == 1 || out.len() < MIN_PARALLEL_LEN {
+        ct_merge(a, b, out);
+    } else {
+        assert_eq!(
+            out.len(),
+            a.len() + b.len(),
+            "output length must equal the combined input length"
+        );
+        bitonic_merge_input(a, b, out);
+        par_merge(out, threads);
+    }
+}
+
+impl<T: CtOrd + Send> ConstantTimeParallelSort for [T] {
+    fn ct_par_sort(&mut self) {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        if threads == 1 || self.len() < MIN_PARALLEL_LEN {
+            self.ct_sort();
+        } else {
+            par_sort(self, threads);
+        }
+    }
+}
+
+/// Shares a slice between threads that access pairwise disjoint elements of it.
+struct SharedSlice<T>(*mut T);
+
+// SAFETY: threads only ever touch disjoint elements, separated by a barrier between layers.
+unsafe impl<T: Send> Sync for SharedSlice<T> {}
+
+impl<T: CtOrd> SharedSlice<T> {
+    /// # Safety
+    ///
+    /// `i` and `j` must be in bounds, and no other thread may access them concurrently.
+    #[inline(always)]
+    unsafe fn compare_exchange(&self, i: usize, j: usize) {
+        let (a, b) = (self.0.add(i), self.0.add(j));
+        let (mut x, mut y) = (*a, *b);
+        let mask = x.ct_gt_mask(y);
+        T::conditional_swap(&mut x, &mut y, mask);
+        *a = x;
+        *b = y;
+    }
+}
+
+fn par_sort<T: CtOrd + Send>(slice: &mut [T], threads: usize) {
+    let len = slice.len();
+    let elements = SharedSlice(slice.as_mut_ptr());
+    let barrier = Barrier::new(threads);
+    thread::scope(|scope| {
+        for thread in 0..threads {
+            let (elements, barrier) = (&elements, &barrier);
+            scope.spawn(move || {
+                network_layers(len, |layer| {
+                    let share = (layer.end - layer.begin).div_ceil(threads);
+                    let begin = layer.begin + (share * thread).min(layer.end - layer.begin);
+                    let end = (begin + share).min(layer.end);
+                    stride_runs(begin, end, layer.p, |start, count| {
+                        for i in start..(start + count) {
+                            // SAFETY: the comparators of a layer touch pairwise disjoint
+                            // elements within the slice, and each one belongs to exactly one
+                            // thread's share.
+                            unsafe { elements.compare_exchange(i + layer.lo, i + layer.hi) };
+                        }
+                    });
+                    barrier.wait();
+                });
+            });
+        }
+    });
+}
+
+/// Reorders `data` like [`apply_permutation`], gathering from a scratch copy on one scoped thread
+/// per available core. Each thread writes a fixed range of output indices, so the division of
+/// work depends only on the length; as in `apply_permutation`, the reads are addressed by `perm`,
+/// which is not treated as secret. Short slices are reordered on the calling thread.
+///
+/// Panics if `perm` differs in length from `data`, and, in debug builds, if it is not a
+/// permutation of `0..data.len()`.
+pub fn par_apply_permutation<T: Copy + Send + Sync>(data: &mut [T], perm: &[u32]) {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if threads == 1 || data.len() < MIN_PARALLEL_LEN {
+        apply_permutation(data, perm);
+    } else {
+        par_gather(data, perm, threads);
+    }
+}
+
+fn par_gather<T: Copy + Send + Sync>(data: &mut [T], perm: &[u32], threads: usize) {
+    assert_eq!(
+        data.len(),
+        perm.len(),
+        "permutation differs in length from data"
+    );
+    debug_assert!(is_permutation(perm), "not a permutation");
+    let old: Scratch<T> = data.iter().copied().collect();
+    let share = data.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        for (out, perm) in data.chunks_mut(share).zip(perm.chunks(share)) {
+            let old = &old;
+            scope.spawn(move || {
+                for (dst, &index) in out.iter_mut().zip(perm) {
+                    *dst = old[index as usize];
+                }
+            });
+        }
+    });
+}
+
+/// Sorts every slice in `slices` ascending with [`ct_sort`](ConstantTimeSort::ct_sort), spread
+/// over one scoped thread per available core. Unlike [`ct_sort_batch`](crate::ct_sort_batch), the
+/// slices may differ in length. Each thread takes a fixed, contiguous range of the slices, so the
+/// division of work depends only on their number. Small batches are sorted on the calling
+/// thread.
+pub fn par_ct_sort_batch<T: CtOrd + Send>(slices: &mut [&mut [T]]) {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let total: usize = slices.iter().map(|slice| slice.len()).sum();
+    if threads == 1 || slices.len() == 1 || total < MIN_PARALLEL_LEN {
+        for slice in slices.iter_mut() {
+            slice.ct_sort();
+        }
+    } else {
+        par_sort_batch(slices, threads);
+    }
+}
+
+fn par_sort_batch<T: CtOrd + Send>(slices: &mut [&mut [T]], threads: usize) {
+    let share = slices.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        for group in slices.chunks_mut(share) {
+            scope.spawn(move || {
+                for slice in group.iter_mut() {
+                    slice.ct_sort();
+                }
+            });
+        }
+    });
+}
+
+/// Returns the layers of the bitonic merge network for `len` elements. Each layer lists blocks
+/// `(lo, len)`, which stand for the comparators `(i, i + m)` for `i` in `lo..lo + len - m`, where
+/// `m` is the largest power of two below `len`. Blocks of one layer are disjoint, so running the
+/// layers in order is equivalent to the depth-first recursion of `bitonic_merge_network`.
+fn bitonic_merge_layers(len: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut layers = Vec::new();
+    let mut blocks = std::vec![(0, len)];
+    blocks.retain(|&(_, len)| len > 1);
+    while !blocks.is_empty() {
+        let children = blocks
+            .iter()
+            .flat_map(|&(lo, len)| {
+                let m = crate::top(len).unwrap();
+                [(lo, m), (lo + m, len - m)]
+            })
+            .filter(|&(_, len)| len > 1)
+            .collect();
+        layers.push(blocks);
+        blocks = children;
+    }
+    layers
+}
+
+fn par_merge<T: CtOrd + Send>(slice: &mut [T], threads: usize) {
+    let layers = bitonic_merge_layers(slice.len());
+    let elements = SharedSlice(slice.as_mut_ptr());
+    let barrier = Barrier::new(threads);
+    thread::scope(|scope| {
+        for thread in 0..threads {
+            let (layers, elements, barrier) = (&layers, &elements, &barrier);
+            scope.spawn(move || {
+                for blocks in layers {
+                    let width = |len: usize| len - crate::top(len).unwrap();
+                    let total: usize = blocks.iter().map(|&(_, len)| width(len)).sum();
+                    let share = total.div_ceil(threads);
+                    let begin = (share * thread).min(total);
+                    let end = (begin + share).min(total);
+                    let mut offset = 0;
+                    for &(lo, len) in blocks {
+                        let count = width(len);
+                        let m = len - count;
+                        let first = begin.clamp(offset, offset + count) - offset;
+                        let last = end.clamp(offset, offset + count) - offset;
+                        for i in (lo + first)..(lo + last) {
+                            // SAFETY: the blocks of a layer are disjoint and their comparators
+                            // touch pairwise disjoint elements within the slice, and each one
+                            // belongs to exactly one thread's share.
+                            unsafe { elements.compare_exchange(i, i + m) };
+                        }
+                        offset += count;
+                    }
+                    barrier.wait();
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use super::{par_gather, par_merge, par_sort, par_sort_batch};
+    use crate::merge::bitonic_merge_input;
+    use crate::{
+        apply_permutation, ct_merge, ct_par_merge, par_apply_permutation, par_ct_sort_batch,
+        ConstantTimeParallelSort, ConstantTimeSort,
+    };
+
+    #[test]
+    fn par_sort_matches_ct_sort() {
+        for count in 0..300 {
+            for threads in [2, 3, 8] {
+                let mut vec: Vec<u32> = rand::rng().random_iter().take(count).collect();
+                let mut expected = vec.to_vec();
+                par_sort(&mut vec, threads);
+                expected.ct_sort();
+                assert_eq!(vec, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn par_sort_large() {
+        let mut vec: Vec<u64> = rand::rng().random_iter().take(100_000).collect();
+        let mut expected = vec.to_vec();
+        vec.ct_par_sort();
+        expected.sort();
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn par_merge_matches_ct_merge() {
+        for a_len in 0..40 {
+            for b_len in 0..40 {
+                for threads in [2, 3, 8] {
+                    let mut a: Vec<u32> = rand::rng().random_iter().take(a_len).collect();
+                    let mut b: Vec<u32> = rand::rng().random_iter().take(b_len).collect();
+                    a.sort();
+                    b.sort();
+                    let mut out = std::vec![0; a_len + b_len];
+                    let mut expected = out.to_vec();
+                    bitonic_merge_input(&a, &b, &mut out);
+                    par_merge(&mut out, threads);
+                    ct_merge(&a, &b, &mut expected);
+                    assert_eq!(out, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn par_merge_large() {
+        let mut a: Vec<u64> = rand::rng().random_iter().take(60_000).collect();
+        let mut b: Vec<u64> = rand::rng().random_iter().take(45_000).collect();
+        a.sort();
+        b.sort();
+        let mut out = std::vec![0; a.len() + b.len()];
+        ct_par_merge(&a, &b, &mut out);
+        let mut expected = [a, b].concat();
+        expected.sort();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn par_gather_matches_apply_permutation() {
+        for count in 0..200 {
+            for threads in [2, 3, 8] {
+                let keys: Vec<u16> = rand::rng().random_iter().take(count).collect();
+                let perm = keys.ct_argsort();
+                let mut data: Vec<u64> = rand::rng().random_iter().take(count).collect();
+                let mut expected = data.to_vec();
+                par_gather(&mut data, &perm, threads);
+                apply_permutation(&mut expected, &perm);
+                assert_eq!(data, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn par_apply_permutation_large() {
+        let keys: Vec<u32> = rand::rng().random_iter().take(100_000).collect();
+        let perm = keys.ct_argsort();
+        let mut data = keys.to_vec();
+        par_apply_permutation(&mut data, &perm);
+        let mut expected = keys;
+        expected.sort();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn par_sort_batch_matches_ct_sort() {
+        for count in 0..20 {
+            for threads in [2, 3, 8] {
+                let mut rows: Vec<Vec<i32>> = (0..count)
+                    .map(|_| {
+                        let len = rand::rng().random_range(0..100);
+                        rand::rng().random_iter().take(len).collect()
+                    })
+                    .collect();
+                let mut expected = rows.to_vec();
+                let mut views: Vec<&mut [i32]> = rows.iter_mut().map(|row| &mut row[..]).collect();
+                par_sort_batch(&mut views, threads);
+                for row in &mut expected {
+                    row.sort();
+                }
+                assert_eq!(rows, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn par_ct_sort_batch_large() {
+        let mut rows: Vec<Vec<u32>> = (0..1000)
+            .map(|_| rand::rng().random_iter().take(50).collect())
+            .collect();
+        let mut expected = rows.to_vec();
+        let mut views: Vec<&mut [u32]> = rows.iter_mut().map(|row| &mut row[..]).collect();
+        par_ct_sort_batch(&mut views);
+        for row in &mut expected {
+            row.sort();
+        }
+        assert_eq!(rows, expected);
+    }
+}
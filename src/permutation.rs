@@ -0,0 +1,116 @@
+//! Reordering by permutations such as the ones [`ct_argsort`] returns. The permutation itself is
+//! not treated as secret: it is used directly as a memory index.
+//!
+//! [`ct_argsort`]: crate::ConstantTimeSort::ct_argsort
+
+use alloc::vec::Vec;
+
+use crate::scratch::Scratch;
+
+/// Reorders `data` so that `data[i]` becomes the old `data[perm[i]]`, for every `i`. The elements
+/// are copied into a scratch buffer and gathered back, so no element value is ever compared or
+/// branched on. With the permutation from [`ct_argsort`] of a key slice, this sorts `data` by
+/// those keys.
+///
+/// Panics if `perm` differs in length from `data`, and, in debug builds, if it is not a
+/// permutation of `0..data.len()`.
+///
+/// [`ct_argsort`]: crate::ConstantTimeSort::ct_argsort
+pub fn apply_permutation<T: Copy>(data: &mut [T], perm: &[u32]) {
+    assert_eq!(
+        data.len(),
+        perm.len(),
+        "permutation differs in length from data"
+    );
+    debug_assert!(is_permutation(perm), "not a permutation");
+    let old: Scratch<T> = data.iter().copied().collect();
+    for (dst, &index) in data.iter_mut().zip(perm) {
+        *dst = old[index as usize];
+    }
+}
+
+/// Returns the inverse of `perm`, so that `inverse[perm[i]] == i` for every `i`. Applying `perm`
+/// and then its inverse with [`apply_permutation`] restores the original order; after
+/// [`ct_argsort`], the inverse maps each original index to its sorted position.
+///
+/// This is a plain scatter whose writes are addressed by `perm`, so it assumes, like the rest of
+/// this module, that the permutation is not secret.
+///
+/// Panics if `perm` is not a permutation of `0..perm.len()`.
+///
+/// [`ct_argsort`]: crate::ConstantTimeSort::ct_argsort
+pub fn invert_permutation(perm: &[u32]) -> Vec<u32> {
+    assert!(is_permutation(perm), "not a permutation");
+    let mut inverse = alloc::vec![0; perm.len()];
+    for (i, &index) in (0..).zip(perm) {
+        inverse[index as usize] = i;
+    }
+    inverse
+}
+
+/// Returns whether `perm` holds every index of `0..perm.len()` exactly once.
+pub(crate) fn is_permutation(perm: &[u32]) -> bool {
+    let mut seen = alloc::vec![false; perm.len()];
+    perm.iter().all(|&index| {
+        let index = index as usize;
+        index < perm.len() && !core::mem::replace(&mut seen[index], true)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{apply_permutation, invert_permutation, ConstantTimeSort};
+
+    #[test]
+    fn apply_argsort_permutation() {
+        for count in 0..256 {
+            let keys: Vec<u8> = rand::rng().random_iter().take(count).collect();
+            let mut values: Vec<(u8, u16)> = keys
+                .iter()
+                .map(|&key| (key, rand::rng().random()))
+                .collect();
+            let mut expected = values.to_vec();
+            apply_permutation(&mut values, &keys.ct_argsort());
+            expected.sort_by_key(|&(key, _)| key);
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn apply_permutation_length_mismatch() {
+        apply_permutation(&mut [1u32, 2, 3], &[0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn apply_permutation_repeated_index() {
+        apply_permutation(&mut [1u32, 2, 3], &[0, 1, 1]);
+    }
+
+    #[test]
+    fn invert_argsort_permutation() {
+        for count in 0..256 {
+            let keys: Vec<u16> = rand::rng().random_iter().take(count).collect();
+            let perm = keys.ct_argsort();
+            let inverse = invert_permutation(&perm);
+            for (i, &index) in perm.iter().enumerate() {
+                assert_eq!(inverse[index as usize] as usize, i);
+            }
+            let mut data = keys.to_vec();
+            apply_permutation(&mut data, &perm);
+            apply_permutation(&mut data, &inverse);
+            assert_eq!(data, keys);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn invert_permutation_out_of_range() {
+        invert_permutation(&[0, 3, 1]);
+    }
+}
@@ -0,0 +1,498 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::{ConstantTimeSort, Scratch};
+use crate::{EqualMask, GreaterThanMask};
+
+/// Read-only queries over a slice whose memory access pattern depends only on its length.
+pub trait ConstantTimeQuery {
+    type Item;
+
+    /// Returns the smallest element, or `None` if the slice is empty. Every element is visited
+    /// exactly once, in order, and selected with a mask rather than a branch.
+    fn ct_min(&self) -> Option<Self::Item>;
+
+    /// Returns the largest element, or `None` if the slice is empty, with the same access
+    /// pattern as [`ct_min`](Self::ct_min).
+    fn ct_max(&self) -> Option<Self::Item>;
+
+    /// Returns the index of the smallest element, the first one if it occurs more than once, or
+    /// `None` if the slice is empty. As in [`ct_min`](Self::ct_min), every element is visited,
+    /// and the tracked value and index are both updated with masks.
+    fn ct_argmin(&self) -> Option<usize>;
+
+    /// Returns `(min, max)` in a single pass, or `None` if the slice is empty.
+    fn ct_minmax(&self) -> Option<(Self::Item, Self::Item)>;
+
+    /// Returns whether the slice is sorted in non-decreasing order. Unlike
+    /// [`slice::is_sorted`], every adjacent pair is checked, so the position of the first
+    /// violation is not leaked.
+    fn ct_is_sorted(&self) -> bool;
+
+    /// Returns the index of the first element not less than `key` in a sorted slice, or the
+    /// slice length if there is none. The search always takes `⌈log₂ len⌉ + 1` steps and updates
+    /// its position with masks rather than branches. Note that the probed addresses still depend
+    /// on `key`, so this does not hide the result from cache-timing observers.
+    fn ct_lower_bound(&self, key: Self::Item) -> usize;
+
+    /// Returns the index of the first element greater than `key` in a sorted slice, or the slice
+    /// length if there is none, with the same fixed step count as
+    /// [`ct_lower_bound`](Self::ct_lower_bound).
+    fn ct_upper_bound(&self, key: Self::Item) -> usize;
+
+    /// Returns whether `key` occurs in the slice by comparing it against every element, so
+    /// neither the presence nor the position of a match is leaked. The slice need not be sorted.
+    fn ct_contains(&self, key: Self::Item) -> bool;
+
+    /// Returns the number of distinct values in a sorted slice by counting the adjacent pairs
+    /// that differ. Every pair is compared, so the positions of duplicates are not leaked. An
+    /// empty slice has none and a single element has one.
+    fn ct_distinct_count(&self) -> usize;
+
+    /// Returns the number of distinct values in a sorted slice; an alias of
+    /// [`ct_distinct_count`](Self::ct_distinct_count), which allocates nothing and reveals only
+    /// the count.
+    #[inline(always)]
+    fn ct_count_distinct_sorted(&self) -> usize {
+        self.ct_distinct_count()
+    }
+
+    /// Returns the number of elements strictly less than `key`. Every element is compared, so
+    /// neither the count nor the positions of the matches are leaked. The slice need not be
+    /// sorted.
+    fn ct_count_lt(&self, key: Self::Item) -> usize;
+
+    /// Returns, for each position, the number of elements strictly smaller than the one there,
+    /// so equal elements share the smaller rank. Every element is compared with every other, in
+    /// `O(len²)` comparisons.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_rank(&self) -> Vec<u32>;
+
+    /// Returns, for each position, the number of distinct values smaller than the one there, so
+    /// equal elements share a rank and the ranks have no gaps. The elements are sorted along
+    /// with their indices, ranked in one pass over the sorted order, and sorted back by index,
+    /// in `O(len log² len)` comparisons.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_dense_rank(&self) -> Vec<u32>;
+}
+
+macro_rules! generate_constant_time_query {
+    ($t:ty) => {
+        impl ConstantTimeQuery for [$t] {
+            type Item = $t;
+
+            fn ct_min(&self) -> Option<$t> {
+                let (&first, rest) = self.split_first()?;
+                Some(
+                    rest.iter()
+                        .fold(first, |min, &x| min ^ ((min ^ x) & min.gt_mask(x))),
+                )
+            }
+
+            fn ct_max(&self) -> Option<$t> {
+                let (&first, rest) = self.split_first()?;
+                Some(
+                    rest.iter()
+                        .fold(first, |max, &x| max ^ ((max ^ x) & x.gt_mask(max))),
+                )
+            }
+
+            fn ct_argmin(&self) -> Option<usize> {
+                let (&first, rest) = self.split_first()?;
+                let (_, index) = rest.iter().zip(1..).fold(
+                    (first, 0usize),
+                    |(min, index), (&x, i): (&$t, usize)| {
+                        let less = min.gt_mask(x);
+                        let take = ((less & 1) as usize).wrapping_neg();
+                        (min ^ ((min ^ x) & less), index ^ ((index ^ i) & take))
+                    },
+                );
+                Some(index)
+            }
+
+            fn ct_minmax(&self) -> Option<($t, $t)> {
+                let (&first, rest) = self.split_first()?;
+                Some(rest.iter().fold((first, first), |(min, max), &x| {
+                    (
+                        min ^ ((min ^ x) & min.gt_mask(x)),
+                        max ^ ((max ^ x) & x.gt_mask(max)),
+                    )
+                }))
+            }
+
+            fn ct_is_sorted(&self) -> bool {
+                let sorted: $t = self
+                    .windows(2)
+                    .fold(!0, |sorted, pair| sorted & !pair[0].gt_mask(pair[1]));
+                sorted != 0
+            }
+
+            fn ct_lower_bound(&self, key: $t) -> usize {
+                if self.is_empty() {
+                    return 0;
+                }
+                let mut base = 0usize;
+                let mut size = self.len();
+                while size > 1 {
+                    let half = size / 2;
+                    let less = ((key.gt_mask(self[base + half]) & 1) as usize).wrapping_neg();
+                    base += half & less;
+                    size -= half;
+                }
+                base + (key.gt_mask(self[base]) & 1) as usize
+            }
+
+            fn ct_upper_bound(&self, key: $t) -> usize {
+                if self.is_empty() {
+                    return 0;
+                }
+                let mut base = 0usize;
+                let mut size = self.len();
+                while size > 1 {
+                    let half = size / 2;
+                    let not_greater =
+                        ((!self[base + half].gt_mask(key) & 1) as usize).wrapping_neg();
+                    base += half & not_greater;
+                    size -= half;
+                }
+                base + (!self[base].gt_mask(key) & 1) as usize
+            }
+
+            fn ct_contains(&self, key: $t) -> bool {
+                let found: $t = self.iter().fold(0, |found, &x| found | x.eq_mask(key));
+                found != 0
+            }
+
+            fn ct_distinct_count(&self) -> usize {
+                self.windows(2).fold(self.len().min(1), |count, pair| {
+                    count + (!pair[0].eq_mask(pair[1]) & 1) as usize
+                })
+            }
+
+            fn ct_count_lt(&self, key: $t) -> usize {
+                self.iter()
+                    .fold(0, |count, &x| count + (key.gt_mask(x) & 1) as usize)
+            }
+
+            #[cfg(feature = "alloc")]
+            fn ct_rank(&self) -> Vec<u32> {
+                assert!(
+                    self.len() <= u32::MAX as usize,
+                    "slice too long for u32 ranks"
+                );
+                self.iter()
+                    .map(|&x| {
+                        self.iter()
+                            .fold(0, |rank, &y| rank + (x.gt_mask(y) & 1) as u32)
+                    })
+                    .collect()
+            }
+
+            #[cfg(feature = "alloc")]
+            fn ct_dense_rank(&self) -> Vec<u32> {
+                assert!(
+                    self.len() <= u32::MAX as usize,
+                    "slice too long for u32 ranks"
+                );
+                let mut sorted: Scratch<($t, u32)> = self.iter().copied().zip(0..).collect();
+                sorted.ct_sort();
+                let mut previous = None;
+                let mut rank = 0;
+                let mut ranked: Scratch<(u32, u32)> = sorted
+                    .iter()
+                    .map(|&(x, index)| {
+                        if let Some(p) = previous {
+                            rank += (!x.eq_mask(p) & 1) as u32;
+                        }
+                        previous = Some(x);
+                        (index, rank)
+                    })
+                    .collect();
+                ranked.ct_sort();
+                ranked.iter().map(|&(_, rank)| rank).collect()
+            }
+        }
+    };
+}
+
+generate_constant_time_query!(u8);
+generate_constant_time_query!(u16);
+generate_constant_time_query!(u32);
+generate_constant_time_query!(u64);
+generate_constant_time_query!(u128);
+generate_constant_time_query!(usize);
+generate_constant_time_query!(i8);
+generate_constant_time_query!(i16);
+generate_constant_time_query!(i32);
+generate_constant_time_query!(i64);
+generate_constant_time_query!(i128);
+generate_constant_time_query!(isize);
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ConstantTimeQuery;
+
+    macro_rules! min_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    assert_eq!(vec.ct_min(), vec.iter().copied().min());
+                }
+            }
+        };
+    }
+
+    min_random!(min_u8_random, u8, 256);
+    min_random!(min_u32_random, u32, 256);
+    min_random!(min_u128_random, u128, 256);
+    min_random!(min_i8_random, i8, 256);
+    min_random!(min_i64_random, i64, 256);
+
+    macro_rules! max_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    assert_eq!(vec.ct_max(), vec.iter().copied().max());
+                }
+            }
+        };
+    }
+
+    max_random!(max_u8_random, u8, 256);
+    max_random!(max_u32_random, u32, 256);
+    max_random!(max_u128_random, u128, 256);
+    max_random!(max_i8_random, i8, 256);
+    max_random!(max_i64_random, i64, 256);
+
+    macro_rules! argmin_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    let expected = vec.ct_min().map(|min| vec.iter().position(|&x| x == min));
+                    assert_eq!(vec.ct_argmin(), expected.flatten());
+                }
+            }
+        };
+    }
+
+    argmin_random!(argmin_u8_random, u8, 256);
+    argmin_random!(argmin_u32_random, u32, 256);
+    argmin_random!(argmin_u128_random, u128, 256);
+    argmin_random!(argmin_i8_random, i8, 256);
+    argmin_random!(argmin_i64_random, i64, 256);
+
+    macro_rules! minmax_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    let expected = vec.iter().copied().min().zip(vec.iter().copied().max());
+                    assert_eq!(vec.ct_minmax(), expected);
+                }
+            }
+        };
+    }
+
+    minmax_random!(minmax_u8_random, u8, 256);
+    minmax_random!(minmax_u32_random, u32, 256);
+    minmax_random!(minmax_u128_random, u128, 256);
+    minmax_random!(minmax_i8_random, i8, 256);
+    minmax_random!(minmax_i64_random, i64, 256);
+
+    macro_rules! is_sorted_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 4) as $t)
+                        .collect();
+                    assert_eq!(vec.ct_is_sorted(), vec.windows(2).all(|w| w[0] <= w[1]));
+                    vec.sort();
+                    assert!(vec.ct_is_sorted());
+                }
+            }
+        };
+    }
+
+    is_sorted_random!(is_sorted_u8_random, u8, 256);
+    is_sorted_random!(is_sorted_u32_random, u32, 256);
+    is_sorted_random!(is_sorted_i64_random, i64, 256);
+    is_sorted_random!(is_sorted_u128_random, u128, 256);
+
+    macro_rules! bounds_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 64) as $t)
+                        .collect();
+                    vec.sort();
+                    for key in 0..66 {
+                        let key = key as $t;
+                        assert_eq!(vec.ct_lower_bound(key), vec.partition_point(|&x| x < key));
+                        assert_eq!(vec.ct_upper_bound(key), vec.partition_point(|&x| x <= key));
+                    }
+                }
+            }
+        };
+    }
+
+    bounds_random!(bounds_u8_random, u8, 128);
+    bounds_random!(bounds_u32_random, u32, 128);
+    bounds_random!(bounds_i64_random, i64, 128);
+    bounds_random!(bounds_u128_random, u128, 128);
+
+    macro_rules! contains_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 64) as $t)
+                        .collect();
+                    for key in 0..66 {
+                        let key = key as $t;
+                        assert_eq!(vec.ct_contains(key), vec.contains(&key));
+                    }
+                }
+            }
+        };
+    }
+
+    contains_random!(contains_u8_random, u8, 128);
+    contains_random!(contains_u32_random, u32, 128);
+    contains_random!(contains_i64_random, i64, 128);
+    contains_random!(contains_u128_random, u128, 128);
+
+    macro_rules! distinct_count_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let mut vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    vec.sort();
+                    let mut expected = vec.to_vec();
+                    expected.dedup();
+                    assert_eq!(vec.ct_distinct_count(), expected.len());
+                    assert_eq!(vec.ct_count_distinct_sorted(), expected.len());
+                }
+            }
+        };
+    }
+
+    distinct_count_random!(distinct_count_u8_random, u8, 128);
+    distinct_count_random!(distinct_count_u32_random, u32, 128);
+    distinct_count_random!(distinct_count_i64_random, i64, 128);
+    distinct_count_random!(distinct_count_u128_random, u128, 128);
+
+    macro_rules! count_lt_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 64) as $t)
+                        .collect();
+                    for key in 0..66 {
+                        let key = key as $t;
+                        assert_eq!(
+                            vec.ct_count_lt(key),
+                            vec.iter().filter(|&&x| x < key).count()
+                        );
+                    }
+                }
+            }
+        };
+    }
+
+    count_lt_random!(count_lt_u8_random, u8, 128);
+    count_lt_random!(count_lt_u32_random, u32, 128);
+    count_lt_random!(count_lt_i64_random, i64, 128);
+    count_lt_random!(count_lt_u128_random, u128, 128);
+
+    macro_rules! rank_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            #[cfg(feature = "alloc")]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    let expected: Vec<u32> = vec
+                        .iter()
+                        .map(|x| vec.iter().filter(|y| *y < x).count() as u32)
+                        .collect();
+                    assert_eq!(vec.ct_rank(), expected);
+                }
+            }
+        };
+    }
+
+    rank_random!(rank_u8_random, u8, 96);
+    rank_random!(rank_i64_random, i64, 96);
+    rank_random!(rank_u128_random, u128, 96);
+
+    macro_rules! dense_rank_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            #[cfg(feature = "alloc")]
+            fn $name() {
+                for count in 0..$n {
+                    let vec: Vec<$t> = rand::rng()
+                        .random_iter::<u8>()
+                        .take(count)
+                        .map(|x| (x % 16) as $t)
+                        .collect();
+                    let mut distinct = vec.to_vec();
+                    distinct.sort();
+                    distinct.dedup();
+                    let expected: Vec<u32> = vec
+                        .iter()
+                        .map(|x| distinct.partition_point(|y| y < x) as u32)
+                        .collect();
+                    assert_eq!(vec.ct_dense_rank(), expected);
+                }
+            }
+        };
+    }
+
+    dense_rank_random!(dense_rank_u8_random, u8, 96);
+    dense_rank_random!(dense_rank_i64_random, i64, 96);
+    dense_rank_random!(dense_rank_u128_random, u128, 96);
+}
@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+
+/// A heap buffer for temporary copies of secret values. With the `zeroize` feature, its bytes
+/// are overwritten with volatile writes when it is dropped, so the copies do not linger in freed
+/// memory.
+pub(crate) struct Scratch<T: Copy>(Vec<T>);
+
+impl<T: Copy> Scratch<T> {
+    /// Overwrites every element with zero bytes and empties the buffer.
+    #[cfg(feature = "zeroize")]
+    fn wipe(&mut self) {
+        let bytes = self.0.len() * core::mem::size_of::<T>();
+        let ptr = self.0.as_mut_ptr() as *mut u8;
+        // `T: Copy` has no destructor, and the emptied buffer is never read as `T` again.
+        self.0.clear();
+        for i in 0..bytes {
+            // SAFETY: `ptr` points to an allocation of at least `bytes` bytes that we own.
+            unsafe { ptr.add(i).write_volatile(0) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<T: Copy> Drop for Scratch<T> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.wipe();
+    }
+}
+
+impl<T: Copy> FromIterator<T> for Scratch<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Scratch<T> {
+        Scratch(iter.into_iter().collect())
+    }
+}
+
+impl<T: Copy> Deref for Scratch<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T: Copy> DerefMut for Scratch<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+#[cfg(all(test, feature = "zeroize"))]
+mod tests {
+    use std::vec;
+
+    use super::Scratch;
+
+    #[test]
+    fn wipe_zeroes_contents() {
+        let mut scratch = Scratch(vec![0xA5A5_A5A5u32; 64]);
+        let ptr = scratch.as_ptr();
+        scratch.wipe();
+        assert!(scratch.is_empty());
+        // The allocation is still alive, so its bytes may be inspected.
+        let bytes = unsafe { core::slice::from_raw_parts(ptr as *const u8, 64 * 4) };
+        assert!(bytes.iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,479 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::cmp::Reverse;
+
+#[cfg(feature = "alloc")]
+use crate::scratch::Scratch;
+use crate::{compare_exchange_at, network, ConstantTimeSort, CtOrd};
+
+/// Order statistics computed with data-independent selection networks.
+pub trait ConstantTimeSelect {
+    type Item;
+
+    /// Returns the `k`-th smallest element (counting from zero). The slice is rearranged in an
+    /// order that depends only on its length and `k`.
+    ///
+    /// Panics if `k >= self.len()`.
+    fn ct_select(&mut self, k: usize) -> Self::Item;
+
+    /// Rearranges the slice like C++'s `nth_element`: afterwards `self[n]` holds the element a
+    /// full sort would put there, with no larger element before it and no smaller one after it.
+    /// This is [`ct_select`](Self::ct_select) without the return value.
+    ///
+    /// Panics if `n >= self.len()`.
+    fn ct_nth_element(&mut self, n: usize);
+
+    /// Returns the lower median, i.e. the element at index `(len - 1) / 2` of the sorted slice.
+    /// The slice is rearranged as by [`ct_select`](Self::ct_select).
+    ///
+    /// Panics if the slice is empty.
+    fn ct_median(&mut self) -> Self::Item;
+
+    /// Returns the element at the fractional rank `numerator / denominator`, i.e. the element at
+    /// index `⌊(len - 1) · numerator / denominator⌋` of the sorted slice, so that `95, 100` gives
+    /// the 95th percentile and `1, 2` the lower median. The index is computed from the fraction
+    /// and the length alone, and the slice is rearranged as by [`ct_select`](Self::ct_select).
+    ///
+    /// Panics if the slice is empty, if `denominator` is zero or if `numerator > denominator`.
+    fn ct_quantile(&mut self, numerator: usize, denominator: usize) -> Self::Item;
+
+    /// Sorts the slice ascending with [`ct_sort`](ConstantTimeSort::ct_sort) and returns its
+    /// first and last elements, the minimum and the maximum. An empty slice has neither and
+    /// gives `None`.
+    fn ct_sort_minmax(&mut self) -> Option<(Self::Item, Self::Item)>;
+
+    /// Moves the `k` smallest elements to the front in ascending order, leaving the rest behind
+    /// them in an order that depends only on the length and `k`. This selects the `k - 1`-th
+    /// element and then sorts only the prefix, which is cheaper than a full sort for small `k`.
+    ///
+    /// Panics if `k > self.len()`.
+    fn ct_partial_sort(&mut self, k: usize);
+
+    /// Moves the `k` smallest elements to the front, in no particular order, leaving the rest
+    /// behind them. Only the selection network for the `k - 1`-th element runs, so this is
+    /// cheaper than [`ct_partial_sort`](Self::ct_partial_sort).
+    ///
+    /// Panics if `k > self.len()`.
+    fn ct_smallest_k_unordered(&mut self, k: usize);
+
+    /// Returns the `k` smallest elements in ascending order, leaving the slice untouched. A
+    /// scratch copy is arranged by [`ct_partial_sort`](Self::ct_partial_sort) and its prefix
+    /// returned. `k` is clamped to the length.
+    #[cfg(feature = "alloc")]
+    fn ct_k_smallest(&self, k: usize) -> Vec<Self::Item>;
+
+    /// Returns the `k` largest elements in descending order, leaving the slice untouched. This is
+    /// [`ct_k_smallest`](Self::ct_k_smallest) run on a copy wrapped in [`Reverse`], which inverts
+    /// every comparison of the selection network. `k` is clamped to the length.
+    #[cfg(feature = "alloc")]
+    fn ct_k_largest(&self, k: usize) -> Vec<Self::Item>;
+
+    /// Rearranges the slice so that every element `<= pivot` precedes every element `> pivot`,
+    /// keeping the relative order within each side, and returns the number of elements
+    /// `<= pivot`. Each element is tagged with its side and original index, and the tags are
+    /// sorted with the elements in tow, so every write goes through a conditional swap at a
+    /// position that depends only on the length.
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_partition(&mut self, pivot: Self::Item) -> usize;
+
+    /// Rearranges the slice into the elements `< pivot`, then those `== pivot`, then those
+    /// `> pivot`, keeping the relative order within each region, and returns the indices where
+    /// the second and the third region start. The regions are formed by the same tag sort as in
+    /// [`ct_partition`](Self::ct_partition).
+    ///
+    /// Panics if the slice has more than `u32::MAX` elements.
+    #[cfg(feature = "alloc")]
+    fn ct_partition3(&mut self, pivot: Self::Item) -> (usize, usize);
+}
+
+/// Calls `compare_exchange(i, j)`, with `i < j`, for every comparator of a network that moves the
+/// `k`-th smallest element to position `k`, with no larger element before it and no smaller
+/// element after it. The sequence depends only on `len` and `k`.
+///
+/// For `k` close to either end, a sorted buffer of the `k + 1` smallest (or `len - k` largest)
+/// elements is maintained with one insertion per remaining element, which costs `O(len·k)`
+/// comparators. Otherwise the full sorting network is cheaper and is used instead.
+fn selection_network<F: FnMut(usize, usize)>(len: usize, k: usize, mut compare_exchange: F) {
+    assert!(k < len, "selection index out of bounds");
+
+    let front = k + 1;
+    let back = len - k;
+    let buffer = front.min(back);
+    let log_len = (usize::BITS - (len - 1).leading_zeros()) as usize;
+    let full_cost = len / 4 * log_len * (log_len + 1);
+    let buffer_cost = buffer / 4 * log_len * (log_len + 1) + (len - buffer) * buffer;
+
+    if buffer_cost >= full_cost {
+        network(len, compare_exchange);
+    } else if front <= back {
+        network(front, &mut compare_exchange);
+        for m in front..len {
+            compare_exchange(k, m);
+            for i in (0..k).rev() {
+                compare_exchange(i, i + 1);
+            }
+        }
+    } else {
+        network(back, |i, j| compare_exchange(k + i, k + j));
+        for m in (0..k).rev() {
+            compare_exchange(m, k);
+            for i in k..(len - 1) {
+                compare_exchange(i, i + 1);
+            }
+        }
+    }
+}
+
+impl<T: CtOrd> ConstantTimeSelect for [T] {
+    type Item = T;
+
+    fn ct_select(&mut self, k: usize) -> T {
+        self.ct_nth_element(k);
+        self[k]
+    }
+
+    fn ct_nth_element(&mut self, n: usize) {
+        selection_network(self.len(), n, |i, j| compare_exchange_at(self, i, j));
+    }
+
+    fn ct_median(&mut self) -> T {
+        assert!(!self.is_empty(), "median of an empty slice");
+        self.ct_select((self.len() - 1) / 2)
+    }
+
+    fn ct_quantile(&mut self, numerator: usize, denominator: usize) -> T {
+        assert!(!self.is_empty(), "quantile of an empty slice");
+        assert!(
+            numerator <= denominator && denominator > 0,
+            "quantile fraction out of range"
+        );
+        let rank = (self.len() - 1) as u128 * numerator as u128 / denominator as u128;
+        self.ct_select(rank as usize)
+    }
+
+    fn ct_sort_minmax(&mut self) -> Option<(T, T)> {
+        self.ct_sort();
+        Some((*self.first()?, *self.last()?))
+    }
+
+    fn ct_partial_sort(&mut self, k: usize) {
+        self.ct_smallest_k_unordered(k);
+        if k > 0 {
+            network(k - 1, |i, j| compare_exchange_at(self, i, j));
+        }
+    }
+
+    fn ct_smallest_k_unordered(&mut self, k: usize) {
+        assert!(k <= self.len(), "partial sort length out of bounds");
+        if k > 0 {
+            selection_network(self.len(), k - 1, |i, j| compare_exchange_at(self, i, j));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_k_smallest(&self, k: usize) -> Vec<T> {
+        let k = k.min(self.len());
+        let mut scratch: Scratch<T> = self.iter().copied().collect();
+        scratch.ct_partial_sort(k);
+        scratch[..k].to_vec()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_k_largest(&self, k: usize) -> Vec<T> {
+        let k = k.min(self.len());
+        let mut scratch: Scratch<Reverse<T>> = self.iter().copied().map(Reverse).collect();
+        scratch.ct_partial_sort(k);
+        scratch[..k].iter().map(|x| x.0).collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_partition(&mut self, pivot: T) -> usize {
+        assert!(
+            self.len() <= u32::MAX as usize,
+            "slice too long for u32 indices"
+        );
+        let mut above = 0;
+        let mut tags: Scratch<u64> = self
+            .iter()
+            .zip(0u64..)
+            .map(|(&x, index)| {
+                let side = (x.ct_gt_mask(pivot).0 & 1) as u64;
+                above += side as usize;
+                side << 32 | index
+            })
+            .collect();
+        tags.ct_sort_with(self);
+        self.len() - above
+    }
+
+    #[cfg(feature = "alloc")]
+    fn ct_partition3(&mut self, pivot: T) -> (usize, usize) {
+        assert!(
+            self.len() <= u32::MAX as usize,
+            "slice too long for u32 indices"
+        );
+        let (mut below, mut above) = (0, 0);
+        let mut tags: Scratch<u64> = self
+            .iter()
+            .zip(0u64..)
+            .map(|(&x, index)| {
+                let (lt, gt) = (pivot.ct_gt_mask(x).bit(), x.ct_gt_mask(pivot).bit());
+                below += lt as usize;
+                above += gt as usize;
+                // 0 for less, 1 for equal and 2 for greater.
+                let side = (1 + gt - lt) as u64;
+                side << 32 | index
+            })
+            .collect();
+        tags.ct_sort_with(self);
+        (below, self.len() - above)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::ConstantTimeSelect;
+
+    macro_rules! select_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 1..$n {
+                    let vec: Vec<$t> = rand::rng().random_iter().take(count).collect();
+                    let mut expected = vec.to_vec();
+                    expected.sort();
+                    for k in 0..count {
+                        let mut selected = vec.to_vec();
+                        assert_eq!(selected.ct_select(k), expected[k]);
+                        assert!(selected[..k].iter().all(|&x| x <= expected[k]));
+                        assert!(selected[k + 1..].iter().all(|&x| x >= expected[k]));
+                    }
+                }
+            }
+        };
+    }
+
+    select_random!(select_u8_random, u8, 96);
+    select_random!(select_u32_random, u32, 96);
+    select_random!(select_i64_random, i64, 96);
+    select_random!(select_u128_random, u128, 96);
+
+    #[test]
+    fn median_lower() {
+        assert_eq!([7u32].ct_median(), 7);
+        assert_eq!([4u32, 1].ct_median(), 1);
+        assert_eq!([3i32, -1, 2].ct_median(), 2);
+        assert_eq!([5u8, 1, 4, 2].ct_median(), 2);
+
+        for count in 1..128 {
+            let mut vec: Vec<u16> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            assert_eq!(vec.ct_median(), expected[(count - 1) / 2]);
+        }
+    }
+
+    #[test]
+    fn quantile_ranks() {
+        for count in 1..128 {
+            let vec: Vec<i32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for (numerator, denominator) in [(0, 1), (1, 1), (1, 2), (95, 100), (1, 3), (2, 3)] {
+                let rank = (count - 1) * numerator / denominator;
+                assert_eq!(
+                    vec.to_vec().ct_quantile(numerator, denominator),
+                    expected[rank]
+                );
+            }
+            assert_eq!(
+                vec.to_vec().ct_quantile(usize::MAX - 1, usize::MAX),
+                expected[count.saturating_sub(2)]
+            );
+        }
+    }
+
+    #[test]
+    fn sort_minmax_random() {
+        assert_eq!([0u32; 0].ct_sort_minmax(), None);
+        for count in 1..128 {
+            let mut vec: Vec<i64> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            assert_eq!(
+                vec.ct_sort_minmax(),
+                Some((expected[0], expected[count - 1]))
+            );
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn quantile_above_one() {
+        [1u32, 2, 3].ct_quantile(3, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn median_empty() {
+        let mut empty: [u32; 0] = [];
+        empty.ct_median();
+    }
+
+    #[test]
+    fn nth_element_partitions() {
+        for count in 1..96 {
+            let vec: Vec<u8> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for n in 0..count {
+                let mut partitioned = vec.to_vec();
+                partitioned.ct_nth_element(n);
+                assert_eq!(partitioned[n], expected[n]);
+                assert!(partitioned[..n].iter().all(|&x| x <= expected[n]));
+                assert!(partitioned[n + 1..].iter().all(|&x| x >= expected[n]));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn nth_element_out_of_bounds() {
+        [1u32, 2, 3].ct_nth_element(3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_out_of_bounds() {
+        [1u32, 2, 3].ct_select(3);
+    }
+
+    #[test]
+    fn partial_sort_random() {
+        for count in 0..96 {
+            let vec: Vec<i32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for k in 0..=count {
+                let mut partial = vec.to_vec();
+                partial.ct_partial_sort(k);
+                assert_eq!(partial[..k], expected[..k]);
+                let mut rest = partial[k..].to_vec();
+                rest.sort();
+                assert_eq!(rest, expected[k..]);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn k_smallest_random() {
+        for count in 0..64 {
+            let vec: Vec<u16> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for k in 0..=count + 2 {
+                assert_eq!(vec.ct_k_smallest(k), expected[..k.min(count)]);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn k_largest_random() {
+        for count in 0..64 {
+            let vec: Vec<i64> = rand::rng().random_iter().take(count).collect();
+            let original = vec.to_vec();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for k in 0..=count + 2 {
+                let tail: Vec<i64> = expected[count - k.min(count)..]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect();
+                assert_eq!(vec.ct_k_largest(k), tail);
+            }
+            assert_eq!(vec, original);
+        }
+    }
+
+    #[test]
+    fn smallest_k_unordered_random() {
+        for count in 0..96 {
+            let vec: Vec<u16> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            for k in 0..=count {
+                let mut selected = vec.to_vec();
+                selected.ct_smallest_k_unordered(k);
+                let (mut front, mut back) = (selected[..k].to_vec(), selected[k..].to_vec());
+                front.sort();
+                back.sort();
+                assert_eq!(front, expected[..k]);
+                assert_eq!(back, expected[k..]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn partial_sort_out_of_bounds() {
+        [1u32, 2, 3].ct_partial_sort(4);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn partition_random() {
+        for count in 0..128 {
+            let vec: Vec<i16> = rand::rng()
+                .random_iter::<i16>()
+                .take(count)
+                .map(|x| x % 16)
+                .collect();
+            for pivot in -17..17 {
+                let mut partitioned = vec.to_vec();
+                let split = partitioned.ct_partition(pivot);
+                let (below, above): (Vec<i16>, Vec<i16>) = vec.iter().partition(|&&x| x <= pivot);
+                assert_eq!(split, below.len());
+                assert_eq!(partitioned[..split], below[..]);
+                assert_eq!(partitioned[split..], above[..]);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn partition3_random() {
+        for count in 0..128 {
+            let vec: Vec<i16> = rand::rng()
+                .random_iter::<i16>()
+                .take(count)
+                .map(|x| x % 8)
+                .collect();
+            for pivot in -9..9 {
+                let mut partitioned = vec.to_vec();
+                let (equal, above) = partitioned.ct_partition3(pivot);
+                let expected: Vec<i16> = [
+                    vec.iter()
+                        .filter(|&&x| x < pivot)
+                        .copied()
+                        .collect::<Vec<_>>(),
+                    vec.iter().filter(|&&x| x == pivot).copied().collect(),
+                    vec.iter().filter(|&&x| x > pivot).copied().collect(),
+                ]
+                .concat();
+                assert_eq!(partitioned, expected);
+                assert_eq!(equal, vec.iter().filter(|&&x| x < pivot).count());
+                assert_eq!(above, vec.iter().filter(|&&x| x <= pivot).count());
+            }
+        }
+    }
+}
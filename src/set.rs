@@ -0,0 +1,158 @@
+//! Set operations on sorted slices and multiset comparison, in time and access patterns that
+//! depend only on the input lengths. Only the length of a returned vector reveals anything about
+//! the contents.
+
+use alloc::vec::Vec;
+
+use crate::scratch::Scratch;
+use crate::{ct_eq, ct_merge_into, ConstantTimeSort, CtOrd, Mask};
+
+/// Returns the elements of the sorted slice `a` that also occur in `b`, in order.
+///
+/// Every element of `a` is compared with every element of `b`, and the matches are moved to the
+/// front with the same tag sort as [`ct_partition`], so the work is `O(a.len() · b.len())`
+/// regardless of the overlap.
+///
+/// Panics if `a` has more than `u32::MAX` elements.
+///
+/// [`ct_partition`]: crate::ConstantTimeSelect::ct_partition
+pub fn ct_intersect<T: CtOrd>(a: &[T], b: &[T]) -> Vec<T> {
+    compact(a, a.iter().map(|&x| occurs_in(x, b)))
+}
+
+/// Returns the elements of the sorted slice `a` that do not occur in `b`, in order. Like
+/// [`ct_intersect`], it compares every element of `a` with every element of `b`.
+///
+/// Panics if `a` has more than `u32::MAX` elements.
+pub fn ct_difference<T: CtOrd>(a: &[T], b: &[T]) -> Vec<T> {
+    compact(a, a.iter().map(|&x| !occurs_in(x, b)))
+}
+
+/// Returns the distinct elements of the sorted slices `a` and `b`, in order.
+///
+/// The inputs are merged with [`ct_merge_into`], every element is compared with its predecessor,
+/// and the first of each run of equal elements is kept by the same compaction as in
+/// [`ct_intersect`].
+///
+/// Panics if `a` and `b` have more than `u32::MAX` elements together.
+pub fn ct_union<T: CtOrd>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut merged: Scratch<T> = a.iter().chain(b).copied().collect();
+    ct_merge_into(a, b, &mut merged);
+    let firsts = merged.iter().enumerate().map(|(k, &x)| match k {
+        0 => Mask::TRUE,
+        _ => !x.ct_eq_mask(merged[k - 1]),
+    });
+    compact(&merged, firsts)
+}
+
+/// Returns whether `a` and `b` hold the same elements with the same multiplicities, in any order.
+///
+/// Sorted copies of both are compared with [`ct_eq`], so for equal lengths the time does not
+/// depend on the contents. Slices of different lengths are rejected without looking at them.
+pub fn ct_multiset_eq<T: CtOrd>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut a: Scratch<T> = a.iter().copied().collect();
+    let mut b: Scratch<T> = b.iter().copied().collect();
+    a.ct_sort();
+    b.ct_sort();
+    ct_eq(&a, &b)
+}
+
+/// Returns a mask that is set if `x` equals any element of `slice`, after scanning all of it.
+fn occurs_in<T: CtOrd>(x: T, slice: &[T]) -> Mask {
+    slice
+        .iter()
+        .fold(Mask::FALSE, |found, &y| found | x.ct_eq_mask(y))
+}
+
+/// Returns the elements of `values` whose mask in `keep` is set, in order. The kept elements are
+/// tagged so that they sort in front of the others, and the tags are sorted with the elements in
+/// tow.
+fn compact<T: CtOrd, I: Iterator<Item = Mask>>(values: &[T], keep: I) -> Vec<T> {
+    assert!(
+        values.len() <= u32::MAX as usize,
+        "slice too long for u32 indices"
+    );
+    let mut kept = 0;
+    let mut tags: Scratch<u64> = keep
+        .zip(0u64..)
+        .map(|(keep, index)| {
+            let bit = keep.bit();
+            kept += bit as usize;
+            ((bit ^ 1) as u64) << 32 | index
+        })
+        .collect();
+    let mut values: Scratch<T> = values.iter().copied().collect();
+    tags.ct_sort_with(&mut values);
+    values[..kept].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ct_difference, ct_intersect, ct_multiset_eq, ct_union};
+
+    /// Returns a sorted vector of distinct values below `bound`.
+    fn random_set(len: usize, bound: u32) -> Vec<u32> {
+        let mut vec: Vec<u32> = (0..len)
+            .map(|_| rand::rng().random_range(0..bound))
+            .collect();
+        vec.sort();
+        vec.dedup();
+        vec
+    }
+
+    #[test]
+    fn intersect_random() {
+        for a_len in 0..40 {
+            for b_len in 0..40 {
+                let (a, b) = (random_set(a_len, 64), random_set(b_len, 64));
+                let expected: Vec<u32> = a.iter().copied().filter(|x| b.contains(x)).collect();
+                assert_eq!(ct_intersect(&a, &b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn union_random() {
+        for a_len in 0..40 {
+            for b_len in 0..40 {
+                let (a, b) = (random_set(a_len, 64), random_set(b_len, 64));
+                let mut expected = [a.to_vec(), b.to_vec()].concat();
+                expected.sort();
+                expected.dedup();
+                assert_eq!(ct_union(&a, &b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn difference_random() {
+        for a_len in 0..40 {
+            for b_len in 0..40 {
+                let (a, b) = (random_set(a_len, 64), random_set(b_len, 64));
+                let expected: Vec<u32> = a.iter().copied().filter(|x| !b.contains(x)).collect();
+                assert_eq!(ct_difference(&a, &b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn multiset_eq_random() {
+        for len in 0..100 {
+            let a: Vec<u8> = (0..len).map(|_| rand::rng().random_range(0..4)).collect();
+            let mut b = a.to_vec();
+            b.reverse();
+            assert!(ct_multiset_eq(&a, &b));
+            if len > 0 {
+                b[rand::rng().random_range(0..len)] ^= 4;
+                assert!(!ct_multiset_eq(&a, &b));
+                assert!(!ct_multiset_eq(&a, &a[1..]));
+            }
+        }
+    }
+}
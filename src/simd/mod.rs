@@ -0,0 +1,151 @@
+//! Vectorized compare-exchange kernels. Each backend handles the longest prefix of a run that it
+//! can process in whole vectors and reports how many pairs that was; the scalar loop finishes
+//! the rest.
+
+#[cfg(all(
+    not(feature = "portable_simd"),
+    target_arch = "aarch64",
+    target_feature = "neon"
+))]
+mod neon;
+#[cfg(feature = "portable_simd")]
+mod portable;
+#[cfg(all(
+    not(feature = "portable_simd"),
+    target_arch = "wasm32",
+    target_feature = "simd128"
+))]
+mod wasm;
+#[cfg(all(not(feature = "portable_simd"), target_arch = "x86_64"))]
+mod x86;
+
+#[cfg(all(
+    not(feature = "portable_simd"),
+    target_arch = "aarch64",
+    target_feature = "neon"
+))]
+use neon::compare_exchange_u32;
+#[cfg(feature = "portable_simd")]
+use portable::compare_exchange_u32;
+#[cfg(all(
+    not(feature = "portable_simd"),
+    target_arch = "wasm32",
+    target_feature = "simd128"
+))]
+use wasm::compare_exchange_u32;
+#[cfg(all(not(feature = "portable_simd"), target_arch = "x86_64"))]
+use x86::{compare_exchange_u16, compare_exchange_u32, compare_exchange_u64, compare_exchange_u8};
+
+use crate::{compare_exchange_scalar, disjoint_runs};
+
+#[cfg(not(any(
+    feature = "portable_simd",
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(target_arch = "wasm32", target_feature = "simd128")
+)))]
+#[inline(always)]
+fn compare_exchange_u32(_lows: &mut [u32], _highs: &mut [u32]) -> usize {
+    0
+}
+
+#[cfg(not(all(not(feature = "portable_simd"), target_arch = "x86_64")))]
+#[inline(always)]
+fn compare_exchange_u8(_lows: &mut [u8], _highs: &mut [u8]) -> usize {
+    0
+}
+
+#[cfg(not(all(not(feature = "portable_simd"), target_arch = "x86_64")))]
+#[inline(always)]
+fn compare_exchange_u16(_lows: &mut [u16], _highs: &mut [u16]) -> usize {
+    0
+}
+
+#[cfg(not(all(not(feature = "portable_simd"), target_arch = "x86_64")))]
+#[inline(always)]
+fn compare_exchange_u64(_lows: &mut [u64], _highs: &mut [u64]) -> usize {
+    0
+}
+
+/// Sorts `arr` without leaving the vector registers, where the target has a kernel for it.
+/// Returns whether it did.
+#[inline(always)]
+pub(crate) fn sort_u32x16(arr: &mut [u32; 16]) -> bool {
+    #[cfg(all(not(feature = "portable_simd"), target_arch = "x86_64"))]
+    {
+        x86::sort_u32x16(arr)
+    }
+    #[cfg(not(all(not(feature = "portable_simd"), target_arch = "x86_64")))]
+    {
+        let _ = arr;
+        false
+    }
+}
+
+/// The `[u32]` override of [`CtOrd::compare_exchange_run`](crate::CtOrd::compare_exchange_run).
+#[inline(always)]
+pub(crate) fn compare_exchange_run_u32(slice: &mut [u32], lo: usize, hi: usize, count: usize) {
+    let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+    let done = compare_exchange_u32(lows, highs);
+    compare_exchange_scalar(&mut lows[done..], &mut highs[done..]);
+}
+
+/// The `[u8]` override of [`CtOrd::compare_exchange_run`](crate::CtOrd::compare_exchange_run).
+#[inline(always)]
+pub(crate) fn compare_exchange_run_u8(slice: &mut [u8], lo: usize, hi: usize, count: usize) {
+    let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+    let done = compare_exchange_u8(lows, highs);
+    compare_exchange_scalar(&mut lows[done..], &mut highs[done..]);
+}
+
+/// The `[u16]` override of [`CtOrd::compare_exchange_run`](crate::CtOrd::compare_exchange_run).
+#[inline(always)]
+pub(crate) fn compare_exchange_run_u16(slice: &mut [u16], lo: usize, hi: usize, count: usize) {
+    let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+    let done = compare_exchange_u16(lows, highs);
+    compare_exchange_scalar(&mut lows[done..], &mut highs[done..]);
+}
+
+/// The `[u64]` override of [`CtOrd::compare_exchange_run`](crate::CtOrd::compare_exchange_run).
+#[inline(always)]
+pub(crate) fn compare_exchange_run_u64(slice: &mut [u64], lo: usize, hi: usize, count: usize) {
+    let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+    let done = compare_exchange_u64(lows, highs);
+    compare_exchange_scalar(&mut lows[done..], &mut highs[done..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    macro_rules! compare_exchange_run_random {
+        ($name:ident, $t:ty, $n:expr) => {
+            #[test]
+            fn $name() {
+                for count in 0..$n {
+                    for (lo, hi) in [(0, count), (count, 0), (3, count + 5)] {
+                        let len = lo.max(hi) + count;
+                        let random: Vec<$t> = rand::rng().random_iter().take(len).collect();
+                        let equal = std::vec![rand::rng().random::<$t>(); len];
+                        for mut vec in [random, equal] {
+                            let mut expected = vec.to_vec();
+                            for k in 0..count {
+                                if expected[lo + k] > expected[hi + k] {
+                                    expected.swap(lo + k, hi + k);
+                                }
+                            }
+                            super::$name(&mut vec, lo, hi, count);
+                            assert_eq!(vec, expected);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    compare_exchange_run_random!(compare_exchange_run_u8, u8, 100);
+    compare_exchange_run_random!(compare_exchange_run_u16, u16, 60);
+    compare_exchange_run_random!(compare_exchange_run_u32, u32, 40);
+    compare_exchange_run_random!(compare_exchange_run_u64, u64, 40);
+}
@@ -0,0 +1,22 @@
+use core::arch::aarch64::{vbslq_u32, vcgtq_u32, vld1q_u32, vst1q_u32};
+
+const LANES: usize = 4;
+
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: NEON is enabled at compile time, and every load and store covers `LANES`
+    // elements that lie within `lows` or `highs`.
+    unsafe {
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES);
+            let high = highs.as_mut_ptr().add(chunk * LANES);
+            let a = vld1q_u32(low);
+            let b = vld1q_u32(high);
+            let swap = vcgtq_u32(a, b);
+            vst1q_u32(low, vbslq_u32(swap, b, a));
+            vst1q_u32(high, vbslq_u32(swap, a, b));
+        }
+    }
+    chunks * LANES
+}
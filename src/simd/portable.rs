@@ -0,0 +1,35 @@
+use core::simd::cmp::SimdPartialOrd;
+use core::simd::{Select, Simd};
+
+/// The vector width: the widest of the `lanes-*` features that are enabled, or else one that
+/// suits the target's vector registers. It only affects speed, never the result.
+const LANES: usize = if cfg!(feature = "lanes-16") {
+    16
+} else if cfg!(feature = "lanes-8") {
+    8
+} else if cfg!(feature = "lanes-4") {
+    4
+} else if cfg!(target_feature = "avx512f") {
+    16
+} else if cfg!(any(target_arch = "aarch64", target_arch = "wasm32")) {
+    4
+} else {
+    8
+};
+
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    let mut done = 0;
+    for (low, high) in lows
+        .chunks_exact_mut(LANES)
+        .zip(highs.chunks_exact_mut(LANES))
+    {
+        let a = Simd::<u32, LANES>::from_slice(low);
+        let b = Simd::<u32, LANES>::from_slice(high);
+        let swap = a.simd_gt(b);
+        swap.select(b, a).copy_to_slice(low);
+        swap.select(a, b).copy_to_slice(high);
+        done += LANES;
+    }
+    done
+}
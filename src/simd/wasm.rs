@@ -0,0 +1,22 @@
+use core::arch::wasm32::{u32x4_gt, v128, v128_bitselect, v128_load, v128_store};
+
+const LANES: usize = 4;
+
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: SIMD128 is enabled at compile time, and every unaligned load and store covers
+    // `LANES` elements that lie within `lows` or `highs`.
+    unsafe {
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES) as *mut v128;
+            let high = highs.as_mut_ptr().add(chunk * LANES) as *mut v128;
+            let a = v128_load(low);
+            let b = v128_load(high);
+            let swap = u32x4_gt(a, b);
+            v128_store(low, v128_bitselect(b, a, swap));
+            v128_store(high, v128_bitselect(a, b, swap));
+        }
+    }
+    chunks * LANES
+}
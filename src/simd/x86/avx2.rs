@@ -0,0 +1,89 @@
+use core::arch::x86_64::{
+    __m256i, _mm256_blendv_epi8, _mm256_cmpgt_epi16, _mm256_cmpgt_epi32, _mm256_cmpgt_epi64,
+    _mm256_cmpgt_epi8, _mm256_loadu_si256, _mm256_set1_epi16, _mm256_set1_epi32,
+    _mm256_set1_epi64x, _mm256_set1_epi8, _mm256_storeu_si256, _mm256_xor_si256,
+};
+
+/// # Safety
+///
+/// The CPU must support AVX2.
+// Unused when AVX-512F is enabled at compile time, which covers `u32` itself.
+#[cfg_attr(target_feature = "avx512f", allow(dead_code))]
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    const LANES: usize = 8;
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: every unaligned load and store covers `LANES` elements that lie within `lows` or
+    // `highs`.
+    unsafe {
+        // `_mm256_cmpgt_epi32` is signed; flipping the sign bit makes it order unsigned lanes.
+        let bias = _mm256_set1_epi32(i32::MIN);
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES) as *mut __m256i;
+            let high = highs.as_mut_ptr().add(chunk * LANES) as *mut __m256i;
+            let a = _mm256_loadu_si256(low);
+            let b = _mm256_loadu_si256(high);
+            let swap = _mm256_cmpgt_epi32(_mm256_xor_si256(a, bias), _mm256_xor_si256(b, bias));
+            _mm256_storeu_si256(low, _mm256_blendv_epi8(a, b, swap));
+            _mm256_storeu_si256(high, _mm256_blendv_epi8(b, a, swap));
+        }
+    }
+    chunks * LANES
+}
+
+/// Defines the kernel for an unsigned type, biased like the one for `u32` so that the signed
+/// comparison orders it.
+macro_rules! compare_exchange_biased {
+    ($(#[$attr:meta])* $name:ident, $t:ty, $lanes:expr, $bias:expr, $cmpgt:ident) => {
+        /// # Safety
+        ///
+        /// The CPU must support AVX2.
+        $(#[$attr])*
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        pub(super) unsafe fn $name(lows: &mut [$t], highs: &mut [$t]) -> usize {
+            const LANES: usize = $lanes;
+            let chunks = lows.len().min(highs.len()) / LANES;
+            // SAFETY: every unaligned load and store covers `LANES` elements that lie within
+            // `lows` or `highs`.
+            unsafe {
+                let bias = $bias;
+                for chunk in 0..chunks {
+                    let low = lows.as_mut_ptr().add(chunk * LANES) as *mut __m256i;
+                    let high = highs.as_mut_ptr().add(chunk * LANES) as *mut __m256i;
+                    let a = _mm256_loadu_si256(low);
+                    let b = _mm256_loadu_si256(high);
+                    let swap = $cmpgt(_mm256_xor_si256(a, bias), _mm256_xor_si256(b, bias));
+                    _mm256_storeu_si256(low, _mm256_blendv_epi8(a, b, swap));
+                    _mm256_storeu_si256(high, _mm256_blendv_epi8(b, a, swap));
+                }
+            }
+            chunks * LANES
+        }
+    };
+}
+
+compare_exchange_biased!(
+    compare_exchange_u8,
+    u8,
+    32,
+    _mm256_set1_epi8(i8::MIN),
+    _mm256_cmpgt_epi8
+);
+compare_exchange_biased!(
+    compare_exchange_u16,
+    u16,
+    16,
+    _mm256_set1_epi16(i16::MIN),
+    _mm256_cmpgt_epi16
+);
+compare_exchange_biased!(
+    // Unused when AVX-512F is enabled at compile time, which covers `u64` itself.
+    #[cfg_attr(target_feature = "avx512f", allow(dead_code))]
+    compare_exchange_u64,
+    u64,
+    4,
+    _mm256_set1_epi64x(i64::MIN),
+    _mm256_cmpgt_epi64
+);
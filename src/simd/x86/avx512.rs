@@ -0,0 +1,124 @@
+use core::arch::x86_64::{
+    __m512i, _mm512_cmpgt_epu32_mask, _mm512_cmpgt_epu64_mask, _mm512_loadu_si512,
+    _mm512_mask_blend_epi32, _mm512_mask_blend_epi64, _mm512_max_epu32, _mm512_min_epu32,
+    _mm512_permutexvar_epi32, _mm512_storeu_si512,
+};
+
+use crate::small::N16;
+
+const LANES: usize = 16;
+
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn compare_exchange_u64(lows: &mut [u64], highs: &mut [u64]) -> usize {
+    const LANES: usize = 8;
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: every unaligned load and store covers `LANES` elements that lie within `lows` or
+    // `highs`.
+    unsafe {
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES) as *mut __m512i;
+            let high = highs.as_mut_ptr().add(chunk * LANES) as *mut __m512i;
+            let a = _mm512_loadu_si512(low);
+            let b = _mm512_loadu_si512(high);
+            let swap = _mm512_cmpgt_epu64_mask(a, b);
+            _mm512_storeu_si512(low, _mm512_mask_blend_epi64(swap, a, b));
+            _mm512_storeu_si512(high, _mm512_mask_blend_epi64(swap, b, a));
+        }
+    }
+    chunks * LANES
+}
+
+/// The layers of the 16-element network, each as the partner of every lane (the lane itself if
+/// it has none) and a mask of the lanes that take the larger value.
+struct Layers {
+    partners: [[u32; LANES]; LANES],
+    upper: [u16; LANES],
+    count: usize,
+}
+
+const N16_LAYERS: Layers = layers(&N16);
+
+/// Groups the comparators into layers greedily, starting a new layer whenever a comparator
+/// touches a lane the current one already uses.
+const fn layers(pairs: &[(usize, usize)]) -> Layers {
+    let mut layers = Layers {
+        partners: [[0; LANES]; LANES],
+        upper: [0; LANES],
+        count: 0,
+    };
+    let mut used = u16::MAX;
+    let mut k = 0;
+    while k < pairs.len() {
+        let (i, j) = pairs[k];
+        if used & (1 << i | 1 << j) != 0 {
+            let mut lane = 0;
+            while lane < LANES {
+                layers.partners[layers.count][lane] = lane as u32;
+                lane += 1;
+            }
+            layers.count += 1;
+            used = 0;
+        }
+        let layer = layers.count - 1;
+        layers.partners[layer][i] = j as u32;
+        layers.partners[layer][j] = i as u32;
+        layers.upper[layer] |= 1 << j;
+        used |= 1 << i | 1 << j;
+        k += 1;
+    }
+    layers
+}
+
+/// Sorts `arr` with the 16-element network, keeping it in one register throughout.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn sort_u32x16(arr: &mut [u32; 16]) {
+    let ptr = arr.as_mut_ptr() as *mut __m512i;
+    // SAFETY: `arr` and every row of `partners` hold exactly `LANES` elements.
+    unsafe {
+        let mut x = _mm512_loadu_si512(ptr);
+        let mut layer = 0;
+        while layer < N16_LAYERS.count {
+            let partners = _mm512_loadu_si512(N16_LAYERS.partners[layer].as_ptr() as *const _);
+            let y = _mm512_permutexvar_epi32(partners, x);
+            let min = _mm512_min_epu32(x, y);
+            let max = _mm512_max_epu32(x, y);
+            x = _mm512_mask_blend_epi32(N16_LAYERS.upper[layer], min, max);
+            layer += 1;
+        }
+        _mm512_storeu_si512(ptr, x);
+    }
+}
+
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[inline]
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: every unaligned load and store covers `LANES` elements that lie within `lows` or
+    // `highs`.
+    unsafe {
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES) as *mut __m512i;
+            let high = highs.as_mut_ptr().add(chunk * LANES) as *mut __m512i;
+            let a = _mm512_loadu_si512(low);
+            let b = _mm512_loadu_si512(high);
+            // AVX-512 compares unsigned lanes directly, into a mask register that drives the
+            // blends.
+            let swap = _mm512_cmpgt_epu32_mask(a, b);
+            _mm512_storeu_si512(low, _mm512_mask_blend_epi32(swap, a, b));
+            _mm512_storeu_si512(high, _mm512_mask_blend_epi32(swap, b, a));
+        }
+    }
+    chunks * LANES
+}
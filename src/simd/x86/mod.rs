@@ -0,0 +1,211 @@
+//! Kernels enabled at compile time are called directly, the widest one first. Without them, and
+//! with `std`, the widest kernel the CPU supports is detected at run time. SSE2 is part of
+//! x86_64, so it is the baseline either way for `u32`. The `u8`, `u16` and `u64` kernels need at
+//! least AVX2 and otherwise leave the whole run to the scalar loop.
+
+#[cfg(any(target_feature = "avx2", feature = "std"))]
+mod avx2;
+#[cfg(any(
+    target_feature = "avx512f",
+    all(not(target_feature = "avx2"), feature = "std")
+))]
+mod avx512;
+#[cfg(not(target_feature = "avx2"))]
+mod sse2;
+
+#[cfg(target_feature = "avx512f")]
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    // SAFETY: AVX-512F is enabled at compile time.
+    unsafe { avx512::compare_exchange_u32(lows, highs) }
+}
+
+#[cfg(all(target_feature = "avx2", not(target_feature = "avx512f")))]
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    // SAFETY: AVX2 is enabled at compile time.
+    unsafe { avx2::compare_exchange_u32(lows, highs) }
+}
+
+#[cfg(all(not(target_feature = "avx2"), not(feature = "std")))]
+#[inline(always)]
+pub(super) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    // SAFETY: SSE2 is part of x86_64.
+    unsafe { sse2::compare_exchange_u32(lows, highs) }
+}
+
+#[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+pub(super) use dispatch::compare_exchange_u32;
+
+/// Defines the entry point of an AVX2-only kernel: called directly with compile-time AVX2,
+/// behind a run-time check with `std`, and a no-op otherwise.
+macro_rules! avx2_only {
+    ($name:ident, $t:ty, $lanes:expr) => {
+        #[cfg(target_feature = "avx2")]
+        #[inline(always)]
+        pub(super) fn $name(lows: &mut [$t], highs: &mut [$t]) -> usize {
+            // SAFETY: AVX2 is enabled at compile time.
+            unsafe { avx2::$name(lows, highs) }
+        }
+
+        #[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+        #[inline(always)]
+        pub(super) fn $name(lows: &mut [$t], highs: &mut [$t]) -> usize {
+            // The check is cached by `std`, and skipped for runs shorter than one vector.
+            if lows.len() < $lanes || !std::is_x86_feature_detected!("avx2") {
+                return 0;
+            }
+            // SAFETY: AVX2 was just detected.
+            unsafe { avx2::$name(lows, highs) }
+        }
+
+        #[cfg(all(not(target_feature = "avx2"), not(feature = "std")))]
+        #[inline(always)]
+        pub(super) fn $name(_lows: &mut [$t], _highs: &mut [$t]) -> usize {
+            0
+        }
+    };
+}
+
+avx2_only!(compare_exchange_u8, u8, 32);
+avx2_only!(compare_exchange_u16, u16, 16);
+
+#[cfg(target_feature = "avx512f")]
+#[inline(always)]
+pub(super) fn compare_exchange_u64(lows: &mut [u64], highs: &mut [u64]) -> usize {
+    // SAFETY: AVX-512F is enabled at compile time.
+    unsafe { avx512::compare_exchange_u64(lows, highs) }
+}
+
+#[cfg(all(target_feature = "avx2", not(target_feature = "avx512f")))]
+#[inline(always)]
+pub(super) fn compare_exchange_u64(lows: &mut [u64], highs: &mut [u64]) -> usize {
+    // SAFETY: AVX2 is enabled at compile time.
+    unsafe { avx2::compare_exchange_u64(lows, highs) }
+}
+
+#[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+#[inline(always)]
+pub(super) fn compare_exchange_u64(lows: &mut [u64], highs: &mut [u64]) -> usize {
+    // The checks are cached by `std`, and skipped for runs shorter than one vector.
+    if lows.len() < 4 {
+        return 0;
+    }
+    if std::is_x86_feature_detected!("avx512f") {
+        // SAFETY: AVX-512F was just detected.
+        return unsafe { avx512::compare_exchange_u64(lows, highs) };
+    }
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 was just detected.
+        return unsafe { avx2::compare_exchange_u64(lows, highs) };
+    }
+    0
+}
+
+#[cfg(all(not(target_feature = "avx2"), not(feature = "std")))]
+#[inline(always)]
+pub(super) fn compare_exchange_u64(_lows: &mut [u64], _highs: &mut [u64]) -> usize {
+    0
+}
+
+/// Sorts `arr` in one AVX-512 register if AVX-512F is enabled at compile time, or, with `std`
+/// and no compile-time AVX2, detected at run time. Returns whether it did.
+#[inline(always)]
+pub(super) fn sort_u32x16(arr: &mut [u32; 16]) -> bool {
+    #[cfg(target_feature = "avx512f")]
+    {
+        // SAFETY: AVX-512F is enabled at compile time.
+        unsafe { avx512::sort_u32x16(arr) };
+        true
+    }
+    #[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+    {
+        if !std::is_x86_feature_detected!("avx512f") {
+            return false;
+        }
+        // SAFETY: AVX-512F was just detected.
+        unsafe { avx512::sort_u32x16(arr) };
+        true
+    }
+    #[cfg(all(
+        not(target_feature = "avx512f"),
+        any(target_feature = "avx2", not(feature = "std"))
+    ))]
+    {
+        let _ = arr;
+        false
+    }
+}
+
+/// Picks the widest kernel the running CPU supports on first use and caches it as a function
+/// pointer. The choice depends only on the CPU, never on the data being sorted.
+#[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+mod dispatch {
+    use core::mem;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    use super::{avx2, avx512, sse2};
+
+    type Kernel = unsafe fn(&mut [u32], &mut [u32]) -> usize;
+
+    static KERNEL: AtomicPtr<()> = AtomicPtr::new(detect as Kernel as *mut ());
+
+    /// Returns the kernels the running CPU supports, widest first, ending with SSE2.
+    pub(super) fn supported() -> impl Iterator<Item = Kernel> {
+        let avx512: Option<Kernel> =
+            std::is_x86_feature_detected!("avx512f").then_some(avx512::compare_exchange_u32);
+        let avx2: Option<Kernel> =
+            std::is_x86_feature_detected!("avx2").then_some(avx2::compare_exchange_u32);
+        avx512
+            .into_iter()
+            .chain(avx2)
+            .chain([sse2::compare_exchange_u32 as Kernel])
+    }
+
+    unsafe fn detect(lows: &mut [u32], highs: &mut [u32]) -> usize {
+        let kernel = supported().next().unwrap_or(sse2::compare_exchange_u32);
+        KERNEL.store(kernel as *mut (), Ordering::Relaxed);
+        // SAFETY: `kernel` only requires features that were just detected.
+        unsafe { kernel(lows, highs) }
+    }
+
+    #[inline(always)]
+    pub(in crate::simd) fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+        // Runs shorter than the narrowest vector would only pay for the indirect call.
+        if lows.len() < 4 {
+            return 0;
+        }
+        // SAFETY: `KERNEL` only ever holds a `Kernel`, and never one that requires features the
+        // CPU lacks.
+        unsafe {
+            let kernel = mem::transmute::<*mut (), Kernel>(KERNEL.load(Ordering::Relaxed));
+            kernel(lows, highs)
+        }
+    }
+}
+
+#[cfg(all(test, not(target_feature = "avx2"), feature = "std"))]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    #[test]
+    fn supported_kernels_match_scalar() {
+        for kernel in super::dispatch::supported() {
+            for count in 0..100 {
+                let mut lows: Vec<u32> = rand::rng().random_iter().take(count).collect();
+                let mut highs: Vec<u32> = rand::rng().random_iter().take(count).collect();
+                let expected: Vec<(u32, u32)> = lows
+                    .iter()
+                    .zip(&highs)
+                    .map(|(&a, &b)| (a.min(b), a.max(b)))
+                    .collect();
+                // SAFETY: `supported` only returns kernels the CPU can run.
+                let done = unsafe { kernel(&mut lows, &mut highs) };
+                assert!(done <= count);
+                let pairs: Vec<(u32, u32)> = lows.into_iter().zip(highs).take(done).collect();
+                assert_eq!(pairs, expected[..done]);
+            }
+        }
+    }
+}
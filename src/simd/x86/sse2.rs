@@ -0,0 +1,34 @@
+use core::arch::x86_64::{
+    __m128i, _mm_and_si128, _mm_cmpgt_epi32, _mm_loadu_si128, _mm_set1_epi32, _mm_storeu_si128,
+    _mm_xor_si128,
+};
+
+const LANES: usize = 4;
+
+/// # Safety
+///
+/// The CPU must support SSE2, which every x86_64 CPU does.
+#[inline]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn compare_exchange_u32(lows: &mut [u32], highs: &mut [u32]) -> usize {
+    let chunks = lows.len().min(highs.len()) / LANES;
+    // SAFETY: every unaligned load and store covers `LANES` elements that lie within `lows` or
+    // `highs`.
+    unsafe {
+        // `_mm_cmpgt_epi32` is signed; flipping the sign bit makes it order unsigned lanes. The
+        // bias only enters the comparison, so the stored values are the original ones.
+        let bias = _mm_set1_epi32(i32::MIN);
+        for chunk in 0..chunks {
+            let low = lows.as_mut_ptr().add(chunk * LANES) as *mut __m128i;
+            let high = highs.as_mut_ptr().add(chunk * LANES) as *mut __m128i;
+            let a = _mm_loadu_si128(low);
+            let b = _mm_loadu_si128(high);
+            let swap = _mm_cmpgt_epi32(_mm_xor_si128(a, bias), _mm_xor_si128(b, bias));
+            // SSE2 has no blend, so the lanes to swap exchange their difference instead.
+            let diff = _mm_and_si128(_mm_xor_si128(a, b), swap);
+            _mm_storeu_si128(low, _mm_xor_si128(a, diff));
+            _mm_storeu_si128(high, _mm_xor_si128(b, diff));
+        }
+    }
+    chunks * LANES
+}
@@ -0,0 +1,255 @@
+//! Fixed networks for short slices, which spare them the loop setup of the generic network.
+//!
+//! From 9 to 16 elements, the networks with the fewest known comparators replace the generic
+//! network. Up to 8 elements the generic network is already optimal. From 17 to 32 elements, the
+//! runs of the generic network are generated at compile time, so the same comparators run from a
+//! flat table.
+//!
+//! The networks for 9, 10, 12, 13 and 16 elements are the published ones (see Knuth, TAOCP
+//! 5.3.4). Those for 11, 14 and 15 elements come from the 12- and 16-element ones by fixing some
+//! inputs to the minimum or maximum, dropping the comparators that no longer do anything and
+//! untangling the result. Each table lists its comparators layer by layer, one layer per line.
+
+use crate::{network_run_array, network_run_count, ConstantTimeSort};
+
+/// Returns the comparators `(i, j)`, with `i < j`, of the smallest known sorting network for
+/// `len` elements, or `None` if the generic network is used for that length.
+pub(crate) const fn optimal_network(len: usize) -> Option<&'static [(usize, usize)]> {
+    match len {
+        9 => Some(&N9),
+        10 => Some(&N10),
+        11 => Some(&N11),
+        12 => Some(&N12),
+        13 => Some(&N13),
+        14 => Some(&N14),
+        15 => Some(&N15),
+        16 => Some(&N16),
+        _ => None,
+    }
+}
+
+/// Returns the runs `(lo, hi, count)` of the generic network for `len` elements, precomputed, or
+/// `None` if they are not.
+pub(crate) const fn precomputed_runs(len: usize) -> Option<&'static [(usize, usize, usize)]> {
+    match len {
+        17 => Some(&R17),
+        18 => Some(&R18),
+        19 => Some(&R19),
+        20 => Some(&R20),
+        21 => Some(&R21),
+        22 => Some(&R22),
+        23 => Some(&R23),
+        24 => Some(&R24),
+        25 => Some(&R25),
+        26 => Some(&R26),
+        27 => Some(&R27),
+        28 => Some(&R28),
+        29 => Some(&R29),
+        30 => Some(&R30),
+        31 => Some(&R31),
+        32 => Some(&R32),
+        _ => None,
+    }
+}
+
+/// Sorts 16 `u32`s with the same result as [`ct_sort`](ConstantTimeSort::ct_sort), running the
+/// whole network in one AVX-512 register where the CPU has AVX-512F, with no loads or stores
+/// between layers. Elsewhere it is `ct_sort`.
+///
+/// ```
+/// let mut arr = [7, 3, 15, 0, 9, 12, 1, 4, 8, 14, 2, 11, 6, 13, 5, 10];
+/// djbsort::sort_u32x16(&mut arr);
+/// assert_eq!(arr, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+/// ```
+pub fn sort_u32x16(arr: &mut [u32; 16]) {
+    if !crate::simd::sort_u32x16(arr) {
+        arr.ct_sort();
+    }
+}
+
+macro_rules! precompute_runs {
+    ($($name:ident = $len:expr),+ $(,)?) => {
+        $(
+            const $name: [(usize, usize, usize); network_run_count($len)] =
+                network_run_array::<{ network_run_count($len) }>($len);
+        )+
+    };
+}
+
+precompute_runs!(
+    R17 = 17,
+    R18 = 18,
+    R19 = 19,
+    R20 = 20,
+    R21 = 21,
+    R22 = 22,
+    R23 = 23,
+    R24 = 24,
+    R25 = 25,
+    R26 = 26,
+    R27 = 27,
+    R28 = 28,
+    R29 = 29,
+    R30 = 30,
+    R31 = 31,
+    R32 = 32,
+);
+
+#[rustfmt::skip]
+const N9: [(usize, usize); 25] = [
+    (0, 3), (1, 7), (2, 5), (4, 8),
+    (0, 7), (2, 4), (3, 8), (5, 6),
+    (0, 2), (1, 3), (4, 5), (7, 8),
+    (1, 4), (3, 6), (5, 7),
+    (0, 1), (2, 4), (3, 5), (6, 8),
+    (2, 3), (4, 5), (6, 7),
+    (1, 2), (3, 4), (5, 6),
+];
+
+#[rustfmt::skip]
+const N10: [(usize, usize); 29] = [
+    (0, 8), (1, 9), (2, 7), (3, 5), (4, 6),
+    (0, 2), (1, 4), (5, 8), (7, 9),
+    (0, 3), (2, 4), (5, 7), (6, 9),
+    (0, 1), (3, 6), (8, 9),
+    (1, 5), (2, 3), (4, 8), (6, 7),
+    (1, 2), (3, 5), (4, 6), (7, 8),
+    (2, 3), (4, 5), (6, 7),
+    (3, 4), (5, 6),
+];
+
+#[rustfmt::skip]
+const N11: [(usize, usize); 35] = [
+    (0, 6), (1, 5), (2, 10), (3, 9), (4, 8),
+    (1, 4), (2, 3), (5, 8), (6, 7), (9, 10),
+    (0, 5), (4, 9), (8, 10),
+    (0, 1), (3, 5), (4, 6), (7, 10), (8, 9),
+    (0, 3), (2, 4), (5, 7), (6, 9),
+    (0, 2), (1, 4), (5, 8), (7, 9),
+    (1, 2), (3, 4), (5, 6), (7, 8),
+    (3, 5), (4, 6),
+    (2, 3), (4, 5), (6, 7),
+];
+
+#[rustfmt::skip]
+const N12: [(usize, usize); 39] = [
+    (0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9),
+    (0, 1), (2, 5), (3, 4), (6, 9), (7, 8), (10, 11),
+    (0, 2), (1, 6), (5, 10), (9, 11),
+    (0, 3), (1, 2), (4, 6), (5, 7), (8, 11), (9, 10),
+    (1, 4), (3, 5), (6, 8), (7, 10),
+    (1, 3), (2, 5), (6, 9), (8, 10),
+    (2, 3), (4, 5), (6, 7), (8, 9),
+    (4, 6), (5, 7),
+    (3, 4), (5, 6), (7, 8),
+];
+
+#[rustfmt::skip]
+const N13: [(usize, usize); 45] = [
+    (0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8),
+    (1, 6), (2, 3), (4, 11), (7, 9), (8, 10),
+    (0, 4), (1, 2), (3, 6), (7, 8), (9, 10), (11, 12),
+    (4, 6), (5, 9), (8, 11), (10, 12),
+    (0, 5), (3, 8), (4, 7), (6, 11), (9, 10),
+    (0, 1), (2, 5), (6, 9), (7, 8), (10, 11),
+    (1, 3), (2, 4), (5, 6), (9, 10),
+    (1, 2), (3, 4), (5, 7), (6, 8),
+    (2, 3), (4, 5), (6, 7), (8, 9),
+    (3, 4), (5, 6),
+];
+
+#[rustfmt::skip]
+const N14: [(usize, usize); 51] = [
+    (0, 13), (1, 12), (2, 6), (3, 4), (5, 9), (7, 8),
+    (3, 11), (5, 10), (0, 7), (1, 2), (6, 12), (8, 13),
+    (3, 5), (0, 1), (2, 11), (4, 6), (7, 10), (8, 9), (12, 13),
+    (0, 3), (1, 5), (2, 8), (9, 11), (4, 7), (6, 10),
+    (1, 3), (5, 12), (2, 4), (7, 9), (6, 8), (10, 11),
+    (1, 2), (3, 4), (6, 7), (8, 9), (10, 13),
+    (2, 3), (4, 5), (10, 12), (11, 13),
+    (4, 6), (5, 7), (8, 10), (9, 12),
+    (3, 4), (5, 6), (7, 8), (9, 10), (11, 12),
+    (6, 7), (8, 9),
+];
+
+#[rustfmt::skip]
+const N15: [(usize, usize); 56] = [
+    (0, 11), (1, 14), (2, 13), (3, 7), (4, 5), (6, 10), (8, 9),
+    (4, 12), (0, 6), (1, 8), (2, 3), (7, 13), (9, 14), (10, 11),
+    (0, 4), (1, 2), (3, 12), (5, 7), (6, 8), (9, 10), (13, 14),
+    (0, 1), (2, 4), (3, 9), (10, 12), (5, 6), (7, 8), (11, 13),
+    (1, 2), (4, 11), (3, 5), (6, 10), (7, 9), (8, 12), (13, 14),
+    (1, 3), (2, 5), (6, 7), (9, 10), (8, 13), (12, 14),
+    (2, 3), (4, 5), (8, 11), (12, 13),
+    (4, 6), (5, 7), (8, 9), (10, 11),
+    (3, 4), (5, 6), (7, 8), (9, 10), (11, 12),
+    (6, 7), (8, 9),
+];
+
+#[rustfmt::skip]
+pub(crate) const N16: [(usize, usize); 60] = [
+    (0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10),
+    (0, 5), (1, 7), (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12),
+    (0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11), (12, 13), (14, 15),
+    (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 14), (13, 15),
+    (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14),
+    (1, 4), (2, 6), (5, 8), (7, 10), (9, 13), (11, 14),
+    (2, 4), (3, 6), (9, 12), (11, 13),
+    (3, 5), (6, 8), (7, 9), (10, 12),
+    (3, 4), (5, 6), (7, 8), (9, 10), (11, 12),
+    (6, 7), (8, 9),
+];
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+
+    use super::{optimal_network, precomputed_runs, sort_u32x16};
+    use crate::network_comparators;
+    use crate::ConstantTimeSort;
+
+    #[test]
+    fn small_networks_sort_every_zero_one_input() {
+        for len in 9..=16 {
+            let pairs = optimal_network(len).unwrap();
+            assert!(pairs.iter().all(|&(i, j)| i < j && j < len));
+            assert_eq!(pairs.len(), network_comparators(len));
+            for bits in 0u32..1 << len {
+                let mut v = bits;
+                for &(i, j) in pairs {
+                    if v >> i & 1 > v >> j & 1 {
+                        v ^= 1 << i | 1 << j;
+                    }
+                }
+                let ones = bits.count_ones() as usize;
+                assert_eq!(v, ((1 << ones) - 1) << (len - ones));
+            }
+        }
+    }
+
+    #[test]
+    fn precomputed_runs_match_generic() {
+        for len in 17..=32 {
+            let mut expected = std::vec::Vec::new();
+            crate::generic_network_runs(len, |lo, hi, count| expected.push((lo, hi, count)));
+            assert_eq!(precomputed_runs(len).unwrap(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn sort_u32x16_matches_ct_sort() {
+        let mut inputs: std::vec::Vec<[u32; 16]> = (0u32..1 << 16)
+            .map(|bits| core::array::from_fn(|i| bits >> i & 1))
+            .collect();
+        inputs.extend((0..10_000).map(|_| rand::rng().random::<[u32; 16]>()));
+        inputs
+            .extend((0..10_000).map(|_| core::array::from_fn(|_| rand::rng().random_range(0..4))));
+        for input in inputs {
+            let mut arr = input;
+            let mut expected = input;
+            sort_u32x16(&mut arr);
+            expected.ct_sort();
+            assert_eq!(arr, expected);
+        }
+    }
+}
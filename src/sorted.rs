@@ -0,0 +1,86 @@
+use alloc::vec::Vec;
+
+use crate::{ConstantTimeSort, CtOrd};
+
+/// Sorting in expression position.
+pub trait ConstantTimeSorted<T> {
+    /// Returns the elements sorted ascending with [`ct_sort`](ConstantTimeSort::ct_sort). A
+    /// `Vec` is sorted in place and handed back; a slice is copied first.
+    fn ct_sorted(self) -> Vec<T>;
+
+    /// Returns a sorted copy of the elements and leaves the original as it is, the borrowing
+    /// counterpart of [`ct_sorted`](Self::ct_sorted).
+    fn ct_sorted_copy(&self) -> Vec<T>;
+}
+
+impl<T: CtOrd> ConstantTimeSorted<T> for Vec<T> {
+    fn ct_sorted(mut self) -> Vec<T> {
+        self.ct_sort();
+        self
+    }
+
+    fn ct_sorted_copy(&self) -> Vec<T> {
+        self[..].ct_sorted()
+    }
+}
+
+impl<T: CtOrd> ConstantTimeSorted<T> for &[T] {
+    fn ct_sorted(self) -> Vec<T> {
+        self.to_vec().ct_sorted()
+    }
+
+    fn ct_sorted_copy(&self) -> Vec<T> {
+        self.ct_sorted()
+    }
+}
+
+/// Collecting an iterator into a sorted `Vec`.
+pub trait CtSortIterator<T>: Iterator<Item = T> {
+    /// Collects the items into a `Vec` and sorts it ascending with
+    /// [`ct_sort`](ConstantTimeSort::ct_sort).
+    fn ct_sorted_collect(self) -> Vec<T>;
+}
+
+impl<T: CtOrd, I: Iterator<Item = T>> CtSortIterator<T> for I {
+    fn ct_sorted_collect(self) -> Vec<T> {
+        self.collect::<Vec<T>>().ct_sorted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ConstantTimeSorted, CtSortIterator};
+
+    #[test]
+    fn sorted_vec_and_slice() {
+        for count in 0..128 {
+            let vec: Vec<i32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            expected.sort();
+            assert_eq!(vec[..].ct_sorted(), expected);
+            let original = vec.to_vec();
+            assert_eq!(vec.ct_sorted_copy(), expected);
+            assert_eq!((&vec[..]).ct_sorted_copy(), expected);
+            assert_eq!(vec, original);
+            assert_eq!(vec.ct_sorted(), expected);
+        }
+    }
+
+    #[test]
+    fn sorted_collect() {
+        for count in 0..128 {
+            let vec: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut expected: Vec<u32> = vec.iter().copied().filter(|x| x % 3 != 0).collect();
+            expected.sort();
+            let sorted = vec
+                .iter()
+                .copied()
+                .filter(|x| x % 3 != 0)
+                .ct_sorted_collect();
+            assert_eq!(sorted, expected);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::vec::Vec;
+
+use crate::{build_network, ConstantTimeSort, CtOrd};
+
+/// The sorting network for one length, generated once and applied to any number of slices.
+/// Generation depends only on the length; applying it touches the same pairs in the same order
+/// for every input.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SortingNetwork {
+    len: usize,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl SortingNetwork {
+    /// Materializes the comparators [`ct_sort`](ConstantTimeSort::ct_sort) uses for `len`
+    /// elements.
+    pub fn new(len: usize) -> SortingNetwork {
+        SortingNetwork {
+            len,
+            pairs: build_network(len),
+        }
+    }
+
+    /// Returns the comparators in execution order.
+    pub fn pairs(&self) -> &[(usize, usize)] {
+        &self.pairs
+    }
+
+    /// Sorts `slice` ascending, with the same result as `ct_sort`.
+    ///
+    /// Panics if `slice` does not have the length the network was built for.
+    pub fn sort<T: CtOrd>(&self, slice: &mut [T]) {
+        assert_eq!(
+            slice.len(),
+            self.len,
+            "slice length differs from network length"
+        );
+        slice.apply_network(&self.pairs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{ConstantTimeSort, SortingNetwork};
+
+    #[test]
+    fn sorting_network_reuse() {
+        for len in 0..200 {
+            let network = SortingNetwork::new(len);
+            for _ in 0..4 {
+                let mut vec: Vec<u64> = rand::rng().random_iter().take(len).collect();
+                let mut expected = vec.to_vec();
+                network.sort(&mut vec);
+                expected.ct_sort();
+                assert_eq!(vec, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sorting_network_length_mismatch() {
+        SortingNetwork::new(4).sort(&mut [3u8, 2, 1]);
+    }
+}
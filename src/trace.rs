@@ -0,0 +1,53 @@
+use alloc::vec::Vec;
+
+use crate::{network, CtOrd};
+
+/// Sorts `slice` ascending through the same comparators as
+/// [`ct_sort`](crate::ConstantTimeSort::ct_sort), one at a time, and returns them in execution
+/// order as `(i, j, swapped)`, where `swapped` tells whether `slice[i]` and `slice[j]` were
+/// exchanged.
+///
+/// The indices depend only on the length, but `swapped` is derived from the data, so the trace
+/// itself leaks the input. This is a debugging and teaching aid; never call it on secrets.
+pub fn trace_sort<T: CtOrd>(slice: &mut [T]) -> Vec<(usize, usize, bool)> {
+    let mut trace = Vec::new();
+    network(slice.len(), |i, j| {
+        let (mut a, mut b) = (slice[i], slice[j]);
+        let mask = a.ct_gt_mask(b);
+        T::conditional_swap(&mut a, &mut b, mask);
+        slice[i] = a;
+        slice[j] = b;
+        trace.push((i, j, mask.into()));
+    });
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use crate::{network_pairs, trace_sort, ConstantTimeSort};
+
+    #[test]
+    fn trace_sort_random() {
+        for count in 0..100 {
+            let mut vec: Vec<u32> = rand::rng().random_iter().take(count).collect();
+            let mut expected = vec.to_vec();
+            let mut replay = vec.to_vec();
+            let trace = trace_sort(&mut vec);
+            expected.ct_sort();
+            assert_eq!(vec, expected);
+
+            let pairs: Vec<_> = trace.iter().map(|&(i, j, _)| (i, j)).collect();
+            assert_eq!(pairs, network_pairs(count).collect::<Vec<_>>());
+            for (i, j, swapped) in trace {
+                assert_eq!(swapped, replay[i] > replay[j]);
+                if swapped {
+                    replay.swap(i, j);
+                }
+            }
+            assert_eq!(replay, expected);
+        }
+    }
+}
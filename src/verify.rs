@@ -0,0 +1,327 @@
+//! Tools for checking, on a given build and target, that sorting does not depend on the data.
+
+use std::hint::black_box;
+use std::time::Instant;
+use std::vec;
+use std::vec::Vec;
+
+use std::cell::RefCell;
+
+use crate::{compare_exchange_scalar, disjoint_runs, ConditionalSwap, ConstantTimeSort, CtOrd};
+use crate::{FromBytes, Mask};
+
+/// Number of timed sorts per call to [`measure_timing_leak`], split between the two classes.
+const MEASUREMENTS: usize = 20_000;
+
+/// The outcome of a two-class timing test.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LeakReport {
+    /// Welch's t-statistic for the difference in mean sort time between the classes.
+    pub t: f64,
+    /// Number of timed sorts of all-zero inputs.
+    pub zeros: usize,
+    /// Number of timed sorts of uniformly random inputs.
+    pub random: usize,
+}
+
+impl LeakReport {
+    /// Whether `|t|` exceeds 4.5, the threshold dudect uses to report a probable leak. Timing is
+    /// noisy, so a single result either way is evidence, not proof.
+    pub fn likely_leaks(&self) -> bool {
+        self.t.abs() > 4.5
+    }
+}
+
+/// Times `ct_sort` on slices of `len` elements, interleaving all-zero inputs with uniformly
+/// random ones in a random order, and compares the two time distributions in the manner of
+/// dudect. A build that compiled to data-independent code should give a `t` close to zero.
+pub fn measure_timing_leak<T: FromBytes + Default>(len: usize) -> LeakReport {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+    let mut classes = [Welford::default(), Welford::default()];
+    let mut vec = vec![T::default(); len];
+    for _ in 0..MEASUREMENTS {
+        let class = (rng.next() & 1) as usize;
+        if class == 0 {
+            vec.fill(T::default());
+        } else {
+            fill_random(&mut vec, &mut rng);
+        }
+        let start = Instant::now();
+        black_box(&mut vec[..]).ct_sort();
+        let elapsed = start.elapsed().as_nanos() as f64;
+        classes[class].push(elapsed);
+    }
+    let [zeros, random] = classes;
+    let t = (zeros.mean - random.mean)
+        / (zeros.variance() / zeros.count as f64 + random.variance() / random.count as f64).sqrt();
+    LeakReport {
+        t: if t.is_nan() { 0.0 } else { t },
+        zeros: zeros.count,
+        random: random.count,
+    }
+}
+
+/// One element access made by the sorting network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    Read(usize),
+    Write(usize),
+}
+
+std::thread_local! {
+    static TRACE: RefCell<Option<Vec<Access>>> = const { RefCell::new(None) };
+}
+
+/// An element that logs the indices every compare-exchange of `ct_sort` reads and writes.
+#[derive(Clone, Copy)]
+struct Traced<T>(T);
+
+impl<T: ConditionalSwap> ConditionalSwap for Traced<T> {
+    fn conditional_swap(a: &mut Self, b: &mut Self, mask: Mask) {
+        T::conditional_swap(&mut a.0, &mut b.0, mask);
+    }
+}
+
+impl<T: CtOrd> CtOrd for Traced<T> {
+    fn ct_gt_mask(self, other: Self) -> Mask {
+        self.0.ct_gt_mask(other.0)
+    }
+
+    fn compare_exchange_run(slice: &mut [Self], lo: usize, hi: usize, count: usize) {
+        TRACE.with(|trace| {
+            if let Some(trace) = trace.borrow_mut().as_mut() {
+                for k in 0..count {
+                    let (i, j) = (lo + k, hi + k);
+                    trace.extend([Access::Read(i), Access::Read(j)]);
+                    trace.extend([Access::Write(i), Access::Write(j)]);
+                }
+            }
+        });
+        let (lows, highs) = disjoint_runs(slice, lo, hi, count);
+        compare_exchange_scalar(lows, highs);
+    }
+}
+
+/// Sorts a copy of `input` with [`ct_sort`] and returns every element access the network made,
+/// in order.
+///
+/// [`ct_sort`]: ConstantTimeSort::ct_sort
+pub fn record_input_accesses<T: CtOrd>(input: &[T]) -> Vec<Access> {
+    let mut traced: Vec<Traced<T>> = input.iter().copied().map(Traced).collect();
+    TRACE.with(|trace| *trace.borrow_mut() = Some(Vec::new()));
+    traced.ct_sort();
+    TRACE.with(|trace| trace.borrow_mut().take().unwrap_or_default())
+}
+
+/// Returns every element access [`ct_sort`] makes on a slice of `len` elements, recorded while
+/// sorting zeros. Since the accesses depend only on the length, any other input of that length
+/// gives the same trace; [`assert_same_accesses`] checks that for concrete inputs.
+///
+/// [`ct_sort`]: ConstantTimeSort::ct_sort
+pub fn record_accesses(len: usize) -> Vec<Access> {
+    record_input_accesses(&vec![0u32; len])
+}
+
+/// Panics unless sorting `a` and sorting `b` access the same elements in the same order.
+pub fn assert_same_accesses<T: CtOrd>(a: &[T], b: &[T]) {
+    let (a, b) = (record_input_accesses(a), record_input_accesses(b));
+    assert!(a == b, "inputs produced different access traces");
+}
+
+fn fill_random<T: FromBytes>(slice: &mut [T], rng: &mut XorShift) {
+    // SAFETY: `FromBytes` types accept any byte pattern, and the bytes cover exactly `slice`.
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(
+            slice.as_mut_ptr() as *mut u8,
+            core::mem::size_of_val(slice),
+        )
+    };
+    for chunk in bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&rng.next().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// Marsaglia's xorshift64, which is plenty for choosing classes and filling inputs.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Running mean and variance.
+#[derive(Default)]
+struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn variance(&self) -> f64 {
+        self.m2 / (self.count.max(2) - 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngExt;
+    use std::vec::Vec;
+
+    use super::Welford;
+    use crate::{assert_same_accesses, measure_timing_leak, record_accesses, Access};
+
+    #[test]
+    fn welford_matches_two_pass() {
+        let samples = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let mut welford = Welford::default();
+        samples.iter().for_each(|&x| welford.push(x));
+        let mean = samples.iter().sum::<f64>() / 8.0;
+        let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / 7.0;
+        assert!((welford.mean - mean).abs() < 1e-12);
+        assert!((welford.variance() - variance).abs() < 1e-12);
+    }
+
+    #[test]
+    fn timing_report_counts_every_measurement() {
+        let report = measure_timing_leak::<u32>(64);
+        assert_eq!(report.zeros + report.random, super::MEASUREMENTS);
+        assert!(report.t.is_finite());
+    }
+
+    #[test]
+    fn access_traces_match() {
+        for len in 0..100 {
+            let trace = record_accesses(len);
+            assert_eq!(trace.len(), 4 * crate::network_comparators(len));
+            assert!(trace.iter().all(|&access| match access {
+                Access::Read(i) | Access::Write(i) => i < len,
+            }));
+            let random: Vec<i64> = rand::rng().random_iter().take(len).collect();
+            let sorted: Vec<i64> = (0..len as i64).collect();
+            assert_same_accesses(&random, &sorted);
+        }
+    }
+}
+
+/// Checks that the compare-exchange compiles without conditional branches, by disassembling the
+/// test binary with `objdump` and reading the instructions of one probe function per integer
+/// width. Only optimized builds are checked, since unoptimized code calls out for every
+/// operation:
+///
+/// ```text
+/// cargo test --release --features verify compare_exchange_is_branch_free
+/// ```
+///
+/// The test passes without checking anything if `objdump` is not installed.
+#[cfg(all(test, target_arch = "x86_64", not(debug_assertions)))]
+mod branch_tests {
+    use std::process::Command;
+    use std::string::String;
+    use std::vec;
+    use std::vec::Vec;
+
+    use crate::compare_exchange_at;
+
+    macro_rules! probes {
+        ($($name:ident: $t:ty),+) => {
+            $(
+                #[no_mangle]
+                #[inline(never)]
+                extern "C" fn $name(pair: &mut [$t; 2]) {
+                    compare_exchange_at(pair, 0, 1);
+                }
+            )+
+
+            const PROBES: &[&str] = &[$(stringify!($name)),+];
+
+            fn call_probes() {
+                $(
+                    let mut pair = [2, 1];
+                    $name(std::hint::black_box(&mut pair));
+                    assert_eq!(pair, [1, 2]);
+                )+
+            }
+        };
+    }
+
+    probes!(
+        djbsort_probe_u8: u8,
+        djbsort_probe_u16: u16,
+        djbsort_probe_u32: u32,
+        djbsort_probe_u64: u64,
+        djbsort_probe_i32: i32,
+        djbsort_probe_i64: i64
+    );
+
+    /// Returns the disassembled instructions of the function `symbol`, one per line.
+    fn disassemble(listing: &str, symbol: &str) -> Vec<String> {
+        let header = std::format!("<{}>:", symbol);
+        listing
+            .lines()
+            .skip_while(|line| !line.ends_with(&header))
+            .skip(1)
+            .take_while(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn compare_exchange_is_branch_free() {
+        call_probes();
+        let exe = std::env::current_exe().unwrap();
+        let output = match Command::new("objdump")
+            .args(["-d", "--no-show-raw-insn"])
+            .arg(&exe)
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => return,
+        };
+        let listing = String::from_utf8_lossy(&output.stdout);
+        for probe in PROBES {
+            // `gt_mask` is kept out of line, so the crate's own callees are checked as well.
+            let mut pending = vec![String::from(*probe)];
+            let mut checked = Vec::new();
+            while let Some(symbol) = pending.pop() {
+                let instructions = disassemble(&listing, &symbol);
+                assert!(!instructions.is_empty(), "{} not found", symbol);
+                for instruction in &instructions {
+                    let mnemonic = instruction.split('\t').nth(1).unwrap_or("").trim();
+                    assert!(
+                        !mnemonic.starts_with('j') || mnemonic.starts_with("jmp"),
+                        "conditional branch in {} (from {}): {}",
+                        symbol,
+                        probe,
+                        instruction
+                    );
+                    let callee = instruction
+                        .split_once('<')
+                        .and_then(|(_, rest)| rest.split_once('>'))
+                        .map(|(callee, _)| callee);
+                    if let Some(callee) = callee {
+                        if mnemonic.starts_with("call")
+                            && callee.contains("djbsort")
+                            && !callee.contains('+')
+                            && !checked.iter().any(|c: &String| c == callee)
+                        {
+                            pending.push(String::from(callee));
+                        }
+                    }
+                }
+                checked.push(symbol);
+            }
+        }
+    }
+}